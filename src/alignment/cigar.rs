@@ -0,0 +1,622 @@
+//! Typed representation of CIGAR strings, with conversions from and to the SAM text
+//! format, the packed BAM encoding and alignment operations.
+
+use std::convert::TryFrom;
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::{Alignment, AlignmentMode, AlignmentOperation};
+
+/// Largest run length that fits into the 28 bits available in the BAM encoding.
+pub const MAX_BAM_LEN: u32 = (1 << 28) - 1;
+
+// Convert the length of a run of operations into a CIGAR operation length.
+pub(super) fn run_len(len: usize) -> Result<u32, CigarError> {
+    u32::try_from(len).map_err(|_| CigarError::RunTooLong(len))
+}
+
+/// A single CIGAR operation together with its run length. The variants cover
+/// all nine operations defined by the SAM specification and are listed in the
+/// order of their BAM operation codes (`MIDNSHP=X`).
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Hash)]
+pub enum CigarOp {
+    /// Alignment match, which can be a sequence match or mismatch (`M`)
+    Match(u32),
+    /// Insertion to the reference (`I`)
+    Ins(u32),
+    /// Deletion from the reference (`D`)
+    Del(u32),
+    /// Skipped region from the reference, e.g. an intron (`N`)
+    RefSkip(u32),
+    /// Soft clipping, the clipped bases are present in the read sequence (`S`)
+    SoftClip(u32),
+    /// Hard clipping, the clipped bases are not present in the read sequence (`H`)
+    HardClip(u32),
+    /// Padding, i.e. silent deletion from the padded reference (`P`)
+    Pad(u32),
+    /// Sequence match (`=`)
+    Equal(u32),
+    /// Sequence mismatch (`X`)
+    Diff(u32),
+}
+
+impl CigarOp {
+    /// Create an operation from its SAM character and run length.
+    pub fn new(op: char, len: u32) -> Result<Self, CigarError> {
+        Ok(match op {
+            'M' => CigarOp::Match(len),
+            'I' => CigarOp::Ins(len),
+            'D' => CigarOp::Del(len),
+            'N' => CigarOp::RefSkip(len),
+            'S' => CigarOp::SoftClip(len),
+            'H' => CigarOp::HardClip(len),
+            'P' => CigarOp::Pad(len),
+            '=' => CigarOp::Equal(len),
+            'X' => CigarOp::Diff(len),
+            _ => return Err(CigarError::InvalidOperation(op)),
+        })
+    }
+
+    /// Run length of the operation.
+    pub fn len(&self) -> u32 {
+        match *self {
+            CigarOp::Match(len)
+            | CigarOp::Ins(len)
+            | CigarOp::Del(len)
+            | CigarOp::RefSkip(len)
+            | CigarOp::SoftClip(len)
+            | CigarOp::HardClip(len)
+            | CigarOp::Pad(len)
+            | CigarOp::Equal(len)
+            | CigarOp::Diff(len) => len,
+        }
+    }
+
+    /// Return `true` if the operation has a run length of zero.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The same kind of operation with a different run length.
+    pub fn with_len(&self, len: u32) -> Self {
+        match *self {
+            CigarOp::Match(_) => CigarOp::Match(len),
+            CigarOp::Ins(_) => CigarOp::Ins(len),
+            CigarOp::Del(_) => CigarOp::Del(len),
+            CigarOp::RefSkip(_) => CigarOp::RefSkip(len),
+            CigarOp::SoftClip(_) => CigarOp::SoftClip(len),
+            CigarOp::HardClip(_) => CigarOp::HardClip(len),
+            CigarOp::Pad(_) => CigarOp::Pad(len),
+            CigarOp::Equal(_) => CigarOp::Equal(len),
+            CigarOp::Diff(_) => CigarOp::Diff(len),
+        }
+    }
+
+    /// Character denoting the operation in the SAM format.
+    pub fn char(&self) -> char {
+        match *self {
+            CigarOp::Match(_) => 'M',
+            CigarOp::Ins(_) => 'I',
+            CigarOp::Del(_) => 'D',
+            CigarOp::RefSkip(_) => 'N',
+            CigarOp::SoftClip(_) => 'S',
+            CigarOp::HardClip(_) => 'H',
+            CigarOp::Pad(_) => 'P',
+            CigarOp::Equal(_) => '=',
+            CigarOp::Diff(_) => 'X',
+        }
+    }
+
+    /// Operation code in the BAM format.
+    pub fn code(&self) -> u32 {
+        match *self {
+            CigarOp::Match(_) => 0,
+            CigarOp::Ins(_) => 1,
+            CigarOp::Del(_) => 2,
+            CigarOp::RefSkip(_) => 3,
+            CigarOp::SoftClip(_) => 4,
+            CigarOp::HardClip(_) => 5,
+            CigarOp::Pad(_) => 6,
+            CigarOp::Equal(_) => 7,
+            CigarOp::Diff(_) => 8,
+        }
+    }
+
+    /// Return `true` if the operation consumes bases of the query sequence.
+    /// Hard clipped bases are considered part of the query.
+    pub fn consumes_query(&self) -> bool {
+        matches!(
+            self,
+            CigarOp::Match(_)
+                | CigarOp::Ins(_)
+                | CigarOp::SoftClip(_)
+                | CigarOp::HardClip(_)
+                | CigarOp::Equal(_)
+                | CigarOp::Diff(_)
+        )
+    }
+
+    /// Return `true` if the operation consumes bases of the reference sequence.
+    pub fn consumes_reference(&self) -> bool {
+        matches!(
+            self,
+            CigarOp::Match(_)
+                | CigarOp::Del(_)
+                | CigarOp::RefSkip(_)
+                | CigarOp::Equal(_)
+                | CigarOp::Diff(_)
+        )
+    }
+
+    /// Return `true` for soft and hard clips.
+    pub fn is_clip(&self) -> bool {
+        matches!(self, CigarOp::SoftClip(_) | CigarOp::HardClip(_))
+    }
+
+    /// Encode the operation as a BAM CIGAR element, with the operation code in the
+    /// lower 4 bits and the run length in the upper 28 bits.
+    ///
+    /// ```
+    /// use bio_types::alignment::CigarOp;
+    /// assert_eq!(CigarOp::Diff(3).to_bam().unwrap(), 3 << 4 | 8);
+    /// ```
+    pub fn to_bam(&self) -> Result<u32, CigarError> {
+        if self.len() > MAX_BAM_LEN {
+            return Err(CigarError::LengthOverflow(self.len()));
+        }
+        Ok(self.len() << 4 | self.code())
+    }
+
+    /// Decode a BAM CIGAR element.
+    pub fn from_bam(value: u32) -> Result<Self, CigarError> {
+        let len = value >> 4;
+        Ok(match value & 0xf {
+            0 => CigarOp::Match(len),
+            1 => CigarOp::Ins(len),
+            2 => CigarOp::Del(len),
+            3 => CigarOp::RefSkip(len),
+            4 => CigarOp::SoftClip(len),
+            5 => CigarOp::HardClip(len),
+            6 => CigarOp::Pad(len),
+            7 => CigarOp::Equal(len),
+            8 => CigarOp::Diff(len),
+            code => return Err(CigarError::InvalidBamOperation(code)),
+        })
+    }
+}
+
+impl Display for CigarOp {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}{}", self.len(), self.char())
+    }
+}
+
+/// A CIGAR, i.e. a sequence of run-length encoded alignment operations.
+///
+/// The display format is the one used in SAM files. An empty CIGAR is
+/// displayed as `*`, denoting an unavailable CIGAR.
+///
+/// ```
+/// use bio_types::alignment::{Cigar, CigarOp};
+/// let cigar: Cigar = "3S5M1I4M".parse().unwrap();
+/// assert_eq!(cigar[1], CigarOp::Match(5));
+/// assert_eq!(cigar.query_len(), 13);
+/// assert_eq!(cigar.reference_len(), 9);
+/// assert_eq!(cigar.to_string(), "3S5M1I4M");
+/// let bam = cigar.to_bam().unwrap();
+/// assert_eq!(bam, [3 << 4 | 4, 5 << 4, 1 << 4 | 1, 4 << 4]);
+/// assert_eq!(Cigar::from_bam(&bam).unwrap(), cigar);
+/// ```
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Default)]
+pub struct Cigar(pub Vec<CigarOp>);
+
+impl Cigar {
+    /// Create a new, empty CIGAR.
+    pub fn new() -> Self {
+        Cigar(Vec::new())
+    }
+
+    /// Append an operation. If the last operation is of the same kind, its run
+    /// length is extended instead, unless the combined length would not fit into a
+    /// `u32`. Zero-length operations are ignored.
+    pub fn push(&mut self, op: CigarOp) {
+        if op.is_empty() {
+            return;
+        }
+        match self.0.last_mut() {
+            Some(last) if last.code() == op.code() => match last.len().checked_add(op.len()) {
+                Some(len) => *last = last.with_len(len),
+                None => self.0.push(op),
+            },
+            _ => self.0.push(op),
+        }
+    }
+
+    /// Number of query bases covered by the CIGAR, including clipped bases.
+    pub fn query_len(&self) -> usize {
+        self.0
+            .iter()
+            .filter(|op| op.consumes_query())
+            .map(|op| op.len() as usize)
+            .sum()
+    }
+
+    /// Number of reference bases covered by the CIGAR.
+    pub fn reference_len(&self) -> usize {
+        self.0
+            .iter()
+            .filter(|op| op.consumes_reference())
+            .map(|op| op.len() as usize)
+            .sum()
+    }
+
+    /// Encode the CIGAR in the packed BAM representation.
+    pub fn to_bam(&self) -> Result<Vec<u32>, CigarError> {
+        self.0.iter().map(|op| op.to_bam()).collect()
+    }
+
+    /// Decode a CIGAR from the packed BAM representation.
+    pub fn from_bam(values: &[u32]) -> Result<Self, CigarError> {
+        Ok(Cigar(
+            values
+                .iter()
+                .map(|&value| CigarOp::from_bam(value))
+                .collect::<Result<_, _>>()?,
+        ))
+    }
+
+    /// Expand the CIGAR into alignment operations. Soft and hard clips become
+    /// `Xclip` operations. `M` is interpreted as `Match`, reference skips (`N`)
+    /// become `RefSkip`, and padding (`P`) is dropped.
+    ///
+    /// ```
+    /// use bio_types::alignment::Cigar;
+    /// use bio_types::alignment::AlignmentOperation::*;
+    /// let cigar: Cigar = "1H1S2=1X1I1D".parse().unwrap();
+    /// assert_eq!(cigar.to_operations(), [Xclip(2), Match, Match, Subst, Ins, Del]);
+    /// ```
+    pub fn to_operations(&self) -> Vec<AlignmentOperation> {
+        let mut ops = Vec::new();
+        for op in self.0.iter() {
+            let len = op.len() as usize;
+            let aln_op = match op {
+                CigarOp::Match(_) | CigarOp::Equal(_) => AlignmentOperation::Match,
+                CigarOp::Diff(_) => AlignmentOperation::Subst,
+                CigarOp::Ins(_) => AlignmentOperation::Ins,
+                CigarOp::Del(_) => AlignmentOperation::Del,
+                CigarOp::RefSkip(_) => AlignmentOperation::RefSkip,
+                CigarOp::SoftClip(_) | CigarOp::HardClip(_) => {
+                    match ops.last_mut() {
+                        Some(AlignmentOperation::Xclip(l)) => *l += len,
+                        _ => ops.push(AlignmentOperation::Xclip(len)),
+                    }
+                    continue;
+                }
+                CigarOp::Pad(_) => continue,
+            };
+            ops.extend(std::iter::repeat(aln_op).take(len));
+        }
+        ops
+    }
+
+    /// Construct an alignment in `Custom` mode from the CIGAR. `ystart` is the
+    /// 0-based position of the first aligned reference base, `xlen` and `ylen` are
+    /// the lengths of the query and the reference sequence. See
+    /// [`Alignment::from_cigar`](struct.Alignment.html#method.from_cigar).
+    pub fn to_alignment(
+        &self,
+        ystart: usize,
+        xlen: usize,
+        ylen: usize,
+    ) -> Result<Alignment, CigarError> {
+        let ops = &self.0;
+        let first = ops.iter().position(|op| !op.is_clip()).unwrap_or(ops.len());
+        let last = ops
+            .iter()
+            .rposition(|op| !op.is_clip())
+            .map_or(first, |i| i + 1);
+
+        // hard clips have to be the outermost operations
+        let mut clip_start = 0;
+        for op in ops[..first].iter() {
+            if let CigarOp::HardClip(_) = op {
+                if clip_start > 0 {
+                    return Err(CigarError::InnerClip('H'));
+                }
+            }
+            clip_start += op.len() as usize;
+        }
+        let mut clip_end = 0;
+        for op in ops[last..].iter().rev() {
+            if let CigarOp::HardClip(_) = op {
+                if clip_end > 0 {
+                    return Err(CigarError::InnerClip('H'));
+                }
+            }
+            clip_end += op.len() as usize;
+        }
+        if let Some(op) = ops[first..last].iter().find(|op| op.is_clip()) {
+            return Err(CigarError::InnerClip(op.char()));
+        }
+
+        let aligned = Cigar(ops[first..last].to_vec());
+        let x_i = clip_start + aligned.query_len() + clip_end;
+        let y_i = ystart + aligned.reference_len();
+        if x_i != xlen {
+            return Err(CigarError::QueryLengthMismatch {
+                cigar: x_i,
+                len: xlen,
+            });
+        }
+        if y_i > ylen {
+            return Err(CigarError::ReferenceOutOfBounds {
+                end: y_i,
+                len: ylen,
+            });
+        }
+
+        let mut operations = Vec::with_capacity(x_i + y_i - ystart + 4);
+        if ystart > 0 {
+            operations.push(AlignmentOperation::Yclip(ystart));
+        }
+        if clip_start > 0 {
+            operations.push(AlignmentOperation::Xclip(clip_start));
+        }
+        operations.extend(aligned.to_operations());
+        if clip_end > 0 {
+            operations.push(AlignmentOperation::Xclip(clip_end));
+        }
+        if ylen > y_i {
+            operations.push(AlignmentOperation::Yclip(ylen - y_i));
+        }
+
+        Ok(Alignment {
+            score: 0,
+            ystart,
+            xstart: clip_start,
+            yend: y_i,
+            xend: xlen - clip_end,
+            ylen,
+            xlen,
+            operations,
+            mode: AlignmentMode::Custom,
+        })
+    }
+
+    /// Return an iterator over the operations.
+    pub fn iter(&self) -> std::slice::Iter<'_, CigarOp> {
+        self.0.iter()
+    }
+}
+
+impl std::ops::Deref for Cigar {
+    type Target = [CigarOp];
+
+    fn deref(&self) -> &[CigarOp] {
+        &self.0
+    }
+}
+
+impl<'a> IntoIterator for &'a Cigar {
+    type Item = &'a CigarOp;
+    type IntoIter = std::slice::Iter<'a, CigarOp>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+impl TryFrom<&[AlignmentOperation]> for Cigar {
+    type Error = CigarError;
+
+    /// Run-length encode alignment operations. Matches and substitutions become
+    /// `=` and `X`, `Xclip` operations become soft clips and `Yclip` operations,
+    /// which have no CIGAR equivalent, are dropped. Fails if a clip is longer than
+    /// `u32::MAX`.
+    fn try_from(operations: &[AlignmentOperation]) -> Result<Self, Self::Error> {
+        let mut cigar = Cigar::new();
+        for op in operations {
+            match *op {
+                AlignmentOperation::Match => cigar.push(CigarOp::Equal(1)),
+                AlignmentOperation::Subst => cigar.push(CigarOp::Diff(1)),
+                AlignmentOperation::Del => cigar.push(CigarOp::Del(1)),
+                AlignmentOperation::RefSkip => cigar.push(CigarOp::RefSkip(1)),
+                AlignmentOperation::Ins => cigar.push(CigarOp::Ins(1)),
+                AlignmentOperation::Xclip(len) => cigar.push(CigarOp::SoftClip(run_len(len)?)),
+                AlignmentOperation::Yclip(_) => {}
+            }
+        }
+        Ok(cigar)
+    }
+}
+
+impl From<&Cigar> for Vec<AlignmentOperation> {
+    fn from(cigar: &Cigar) -> Self {
+        cigar.to_operations()
+    }
+}
+
+impl Display for Cigar {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        if self.0.is_empty() {
+            return f.write_str("*");
+        }
+        for op in self.0.iter() {
+            write!(f, "{}", op)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Cigar {
+    type Err = CigarError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Err(CigarError::Empty);
+        } else if s == "*" {
+            return Ok(Cigar::new());
+        }
+
+        let mut ops = Vec::new();
+        let mut rest = s;
+        while !rest.is_empty() {
+            let digits = rest
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(rest.len());
+            let op = rest[digits..]
+                .chars()
+                .next()
+                .ok_or(CigarError::MissingOperation)?;
+            if digits == 0 {
+                return Err(CigarError::MissingLength(op));
+            }
+            let len = rest[..digits].parse::<u32>()?;
+            ops.push(CigarOp::new(op, len)?);
+            rest = &rest[digits + op.len_utf8()..];
+        }
+        Ok(Cigar(ops))
+    }
+}
+
+/// Errors that arise when parsing, decoding or generating a CIGAR.
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum CigarError {
+    #[error("empty CIGAR string")]
+    Empty,
+    #[error("CIGAR string ends with a length that is not followed by an operation")]
+    MissingOperation,
+    #[error("CIGAR operation {0:?} is not preceded by a length")]
+    MissingLength(char),
+    #[error("invalid CIGAR operation: {0:?}")]
+    InvalidOperation(char),
+    #[error("invalid BAM CIGAR operation code: {0}")]
+    InvalidBamOperation(u32),
+    #[error("CIGAR operation length {0} does not fit into the BAM encoding")]
+    LengthOverflow(u32),
+    #[error("run of {0} operations is too long for a CIGAR operation")]
+    RunTooLong(usize),
+    #[error("error parsing CIGAR operation length")]
+    ParseInt(#[from] std::num::ParseIntError),
+    #[error("clipping is only allowed at the ends of a CIGAR string, found {0:?} in between")]
+    InnerClip(char),
+    #[error("CIGAR covers {cigar} query bases, but the query has length {len}")]
+    QueryLengthMismatch { cigar: usize, len: usize },
+    #[error("CIGAR ends at reference position {end}, beyond the reference length {len}")]
+    ReferenceOutOfBounds { end: usize, len: usize },
+    #[error("alignment ends at query position {end}, beyond the query length {len}")]
+    QueryOutOfBounds { end: usize, len: usize },
+    #[error("alignment operations cover {ops} query bases, but the aligned query range is {xstart}..{xend}")]
+    InconsistentQueryRange {
+        ops: usize,
+        xstart: usize,
+        xend: usize,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alignment::AlignmentOperation::*;
+
+    #[test]
+    fn test_parse_display() {
+        let cigar: Cigar = "2H3S10M1I2D100N1P4=2X5S".parse().unwrap();
+        assert_eq!(
+            cigar.0,
+            [
+                CigarOp::HardClip(2),
+                CigarOp::SoftClip(3),
+                CigarOp::Match(10),
+                CigarOp::Ins(1),
+                CigarOp::Del(2),
+                CigarOp::RefSkip(100),
+                CigarOp::Pad(1),
+                CigarOp::Equal(4),
+                CigarOp::Diff(2),
+                CigarOp::SoftClip(5)
+            ]
+        );
+        assert_eq!(cigar.to_string(), "2H3S10M1I2D100N1P4=2X5S");
+        assert_eq!(cigar.query_len(), 27);
+        assert_eq!(cigar.reference_len(), 118);
+
+        assert_eq!("*".parse::<Cigar>().unwrap(), Cigar::new());
+        assert_eq!(Cigar::new().to_string(), "*");
+        assert_eq!("".parse::<Cigar>(), Err(CigarError::Empty));
+        assert_eq!("5M3".parse::<Cigar>(), Err(CigarError::MissingOperation));
+        assert_eq!("M".parse::<Cigar>(), Err(CigarError::MissingLength('M')));
+        assert_eq!(
+            "5M2Y".parse::<Cigar>(),
+            Err(CigarError::InvalidOperation('Y'))
+        );
+        assert!(matches!(
+            "99999999999M".parse::<Cigar>(),
+            Err(CigarError::ParseInt(_))
+        ));
+    }
+
+    #[test]
+    fn test_bam() {
+        let cigar: Cigar = "1M2I3D4N5S6H7P8=9X".parse().unwrap();
+        let bam = cigar.to_bam().unwrap();
+        assert_eq!(
+            bam,
+            (0..9)
+                .map(|code| (code + 1) << 4 | code)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(Cigar::from_bam(&bam).unwrap(), cigar);
+
+        assert_eq!(
+            CigarOp::from_bam(5 << 4 | 9),
+            Err(CigarError::InvalidBamOperation(9))
+        );
+        assert_eq!(
+            CigarOp::Match(MAX_BAM_LEN + 1).to_bam(),
+            Err(CigarError::LengthOverflow(MAX_BAM_LEN + 1))
+        );
+        assert_eq!(
+            CigarOp::from_bam(CigarOp::Match(MAX_BAM_LEN).to_bam().unwrap()).unwrap(),
+            CigarOp::Match(MAX_BAM_LEN)
+        );
+    }
+
+    #[test]
+    fn test_operations() {
+        let ops = [
+            Yclip(3),
+            Xclip(2),
+            Match,
+            Match,
+            Subst,
+            Ins,
+            Ins,
+            Del,
+            Match,
+            Xclip(1),
+        ];
+        let cigar = Cigar::try_from(&ops[..]).unwrap();
+        assert_eq!(cigar.to_string(), "2S2=1X2I1D1=1S");
+        assert_eq!(Vec::from(&cigar), ops[1..]);
+
+        let mut cigar = Cigar::new();
+        cigar.push(CigarOp::Match(2));
+        cigar.push(CigarOp::Match(0));
+        cigar.push(CigarOp::Match(3));
+        cigar.push(CigarOp::Ins(1));
+        assert_eq!(cigar.0, [CigarOp::Match(5), CigarOp::Ins(1)]);
+        cigar.push(CigarOp::Ins(u32::MAX));
+        assert_eq!(cigar[2], CigarOp::Ins(u32::MAX));
+        assert_eq!(
+            Cigar::try_from(&[AlignmentOperation::Xclip(1 << 32)][..]),
+            Err(CigarError::RunTooLong(1 << 32))
+        );
+    }
+}
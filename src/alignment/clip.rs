@@ -0,0 +1,132 @@
+//! Normalization of clipping. In `Custom` mode, the unaligned prefixes and suffixes
+//! of x and y are given by explicit `Xclip` and `Yclip` operations, whereas in the
+//! standard modes they are implied by the aligned ranges. Since both are determined
+//! by the aligned ranges, alignments can be converted between the two
+//! representations without loss.
+
+use super::validate::is_clip;
+use super::{Alignment, AlignmentError, AlignmentMode};
+
+impl Alignment {
+    /// Convert the alignment to the given mode. In `Custom` mode, the clip operations
+    /// are rebuilt from the aligned ranges, in canonical order (`Yclip`, `Xclip`,
+    /// aligned operations, `Xclip`, `Yclip`) and without empty clips. In the standard
+    /// modes, the clip operations are removed. The score is kept as it is.
+    ///
+    /// An error is returned if the alignment is not valid (see
+    /// [`validate`](#method.validate)) or does not fit the requested mode, e.g. if a
+    /// `Global` alignment is requested but the alignment does not cover x and y.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bio_types::alignment::{Alignment,AlignmentError,AlignmentMode};
+    /// use bio_types::alignment::AlignmentOperation::*;
+    /// let alignment = Alignment::from_cigar("3S4=1I2=", 5, 10, 20).unwrap();
+    /// let local = alignment.to_mode(AlignmentMode::Local).unwrap();
+    /// assert_eq!(local.operations, [Match, Match, Match, Match, Ins, Match, Match]);
+    /// assert_eq!((local.xstart, local.ystart), (3, 5));
+    /// assert_eq!(local.to_mode(AlignmentMode::Custom), Ok(alignment.clone()));
+    /// assert_eq!(
+    ///     alignment.to_mode(AlignmentMode::Semiglobal),
+    ///     Err(AlignmentError::IncompleteQuery(AlignmentMode::Semiglobal))
+    /// );
+    /// ```
+    pub fn to_mode(&self, mode: AlignmentMode) -> Result<Alignment, AlignmentError> {
+        self.validate()?;
+        let aligned = self
+            .operations
+            .iter()
+            .filter(|op| !is_clip(op))
+            .cloned()
+            .collect();
+        let alignment =
+            self.with_aligned_operations(aligned, self.xstart, self.ystart, mode, self.score);
+        alignment.validate()?;
+        Ok(alignment)
+    }
+
+    /// Convert the alignment to `Custom` mode, with explicit clip operations. This
+    /// gives the same result for equivalent alignments in any mode.
+    pub fn to_explicit_clips(&self) -> Result<Alignment, AlignmentError> {
+        self.to_mode(AlignmentMode::Custom)
+    }
+
+    /// Convert the alignment to the most specific standard mode, without clip
+    /// operations: `Global` if it covers both x and y, `Semiglobal` if it covers x,
+    /// and `Local` otherwise. This gives the same result for equivalent alignments
+    /// in any mode.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bio_types::alignment::{Alignment,AlignmentMode};
+    /// use bio_types::alignment::AlignmentOperation::*;
+    /// let alignment = Alignment::from_cigar("4=1I2=", 5, 7, 20).unwrap();
+    /// let implicit = alignment.to_implicit_clips().unwrap();
+    /// assert_eq!(implicit.mode, AlignmentMode::Semiglobal);
+    /// assert_eq!(implicit.operations, [Match, Match, Match, Match, Ins, Match, Match]);
+    /// ```
+    pub fn to_implicit_clips(&self) -> Result<Alignment, AlignmentError> {
+        let covers_x = self.xstart == 0 && self.xend == self.xlen;
+        let covers_y = self.ystart == 0 && self.yend == self.ylen;
+        self.to_mode(match (covers_x, covers_y) {
+            (true, true) => AlignmentMode::Global,
+            (true, false) => AlignmentMode::Semiglobal,
+            _ => AlignmentMode::Local,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alignment::AlignmentOperation::*;
+
+    #[test]
+    fn test_normalize_clips() {
+        // the same alignment, with clips in non-canonical order and split up
+        let custom = Alignment {
+            score: 3,
+            xstart: 2,
+            ystart: 1,
+            xend: 5,
+            yend: 4,
+            ylen: 5,
+            xlen: 5,
+            operations: vec![
+                Xclip(2),
+                Yclip(1),
+                Match,
+                Del,
+                Subst,
+                Ins,
+                Yclip(0),
+                Yclip(1),
+            ],
+            mode: AlignmentMode::Custom,
+        };
+        let local = Alignment {
+            operations: vec![Match, Del, Subst, Ins],
+            mode: AlignmentMode::Local,
+            ..custom.clone()
+        };
+        assert_eq!(custom.validate(), Ok(()));
+        assert_eq!(custom.to_implicit_clips(), Ok(local.clone()));
+        assert_eq!(local.to_implicit_clips(), Ok(local.clone()));
+        let explicit = local.to_explicit_clips().unwrap();
+        assert_eq!(
+            explicit.operations,
+            [Yclip(1), Xclip(2), Match, Del, Subst, Ins, Yclip(1)]
+        );
+        assert_eq!(custom.to_explicit_clips(), Ok(explicit));
+        assert_eq!(
+            local.to_mode(AlignmentMode::Global),
+            Err(AlignmentError::IncompleteQuery(AlignmentMode::Global))
+        );
+
+        let mut invalid = local;
+        invalid.operations.push(Match);
+        assert!(invalid.to_explicit_clips().is_err());
+    }
+}
@@ -0,0 +1,400 @@
+//! Generation and interpretation of the minimap2 `cs` tag, which encodes the
+//! differences between query and reference of an alignment.
+
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+use thiserror::Error;
+
+use super::{Alignment, AlignmentOperation, TextSlice};
+
+impl Alignment {
+    /// Calculate the minimap2 `cs` tag of the alignment, given the query sequence x
+    /// and the reference sequence y. In the short form, identical stretches are
+    /// given by their length (`:10`), in the long form by their sequence
+    /// (`=ACGTACGTAC`). Aligned positions are reported as substitutions if the
+    /// (case-insensitive) bases differ, irrespective of whether they are marked as
+    /// `Match` or `Subst`. Reference skips are reported as introns (`~gt10ag`), or
+    /// as deletions if they are too short to hold both splice sites.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bio_types::alignment::{Alignment,AlignmentMode};
+    /// use bio_types::alignment::AlignmentOperation::*;
+    /// let x = b"ACGTTTACGA";
+    /// let y = b"ACGATACCCGA";
+    /// let alignment = Alignment {
+    ///     score: 0,
+    ///     xstart: 0,
+    ///     ystart: 0,
+    ///     xend: 10,
+    ///     yend: 11,
+    ///     ylen: 11,
+    ///     xlen: 10,
+    ///     operations: vec![
+    ///         Match, Match, Match, Subst, Ins, Match, Match, Match, Del, Del, Match, Match,
+    ///     ],
+    ///     mode: AlignmentMode::Global,
+    /// };
+    /// assert_eq!(alignment.cs_tag(x, y, false), ":3*at+t:3-cc:2");
+    /// assert_eq!(alignment.cs_tag(x, y, true), "=ACG*at+t=TAC-cc=GA");
+    /// ```
+    pub fn cs_tag(&self, x: TextSlice, y: TextSlice, long: bool) -> String {
+        let mut cs = Cs::default();
+        // reference range of the current run of skips
+        let mut skip: Option<(usize, usize)> = None;
+        for (x_i, y_i, op) in self.positioned_operations() {
+            if op == AlignmentOperation::RefSkip {
+                skip = Some((skip.map_or(y_i, |(start, _)| start), y_i + 1));
+                continue;
+            }
+            if let Some((start, end)) = skip.take() {
+                cs.push(skipped(y, start, end));
+            }
+            match op {
+                AlignmentOperation::Match | AlignmentOperation::Subst => {
+                    if x[x_i].eq_ignore_ascii_case(&y[y_i]) {
+                        if long {
+                            cs.push(CsOp::Identical(vec![x[x_i].to_ascii_uppercase()]));
+                        } else {
+                            cs.push(CsOp::Match(1));
+                        }
+                    } else {
+                        cs.push(CsOp::Subst(
+                            y[y_i].to_ascii_lowercase(),
+                            x[x_i].to_ascii_lowercase(),
+                        ));
+                    }
+                }
+                AlignmentOperation::Ins => cs.push(CsOp::Ins(vec![x[x_i].to_ascii_lowercase()])),
+                AlignmentOperation::Del => cs.push(CsOp::Del(vec![y[y_i].to_ascii_lowercase()])),
+                _ => {}
+            }
+        }
+        if let Some((start, end)) = skip {
+            cs.push(skipped(y, start, end));
+        }
+        cs.to_string()
+    }
+}
+
+// The `cs` element for the reference skip `y[start..end]`.
+fn skipped(y: TextSlice, start: usize, end: usize) -> CsOp {
+    let bases = y[start..end].to_ascii_lowercase();
+    if bases.len() < 4 {
+        return CsOp::Del(bases);
+    }
+    CsOp::Intron {
+        donor: [bases[0], bases[1]],
+        len: bases.len(),
+        acceptor: [bases[bases.len() - 2], bases[bases.len() - 1]],
+    }
+}
+
+/// An element of a `cs` tag.
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub enum CsOp {
+    /// A number of identical bases (short form, `:n`)
+    Match(usize),
+    /// Identical bases (long form, `=ACGT`)
+    Identical(Vec<u8>),
+    /// A substitution, with the reference and the query base (`*ag`)
+    Subst(u8, u8),
+    /// Bases inserted into the reference (`+ac`)
+    Ins(Vec<u8>),
+    /// Bases deleted from the reference (`-ac`)
+    Del(Vec<u8>),
+    /// An intron with its length and the first and last two reference bases (`~gt10ag`)
+    Intron {
+        donor: [u8; 2],
+        len: usize,
+        acceptor: [u8; 2],
+    },
+}
+
+/// A parsed `cs` tag, in short or long form.
+///
+/// ```
+/// use bio_types::alignment::AlignmentOperation::*;
+/// use bio_types::alignment::cs::Cs;
+/// let cs: Cs = ":2*ag+tt:1-cc=TA".parse().unwrap();
+/// assert_eq!(
+///     cs.operations(),
+///     [Match, Match, Subst, Ins, Ins, Match, Del, Del, Match, Match]
+/// );
+/// assert_eq!(cs.reference(b"CTGTTATA").unwrap(), b"CTaAccTA");
+/// assert_eq!(cs.to_string(), ":2*ag+tt:1-cc=TA");
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Default)]
+pub struct Cs(pub Vec<CsOp>);
+
+impl Cs {
+    /// Append an element, merging it into the last element if both are of the
+    /// same kind (except for substitutions and introns).
+    pub fn push(&mut self, op: CsOp) {
+        match (self.0.last_mut(), op) {
+            (Some(CsOp::Match(n)), CsOp::Match(m)) => *n += m,
+            (Some(CsOp::Identical(a)), CsOp::Identical(b))
+            | (Some(CsOp::Ins(a)), CsOp::Ins(b))
+            | (Some(CsOp::Del(a)), CsOp::Del(b)) => a.extend(b),
+            (_, op) => self.0.push(op),
+        }
+    }
+
+    /// Expand into alignment operations. Introns become reference skips.
+    pub fn operations(&self) -> Vec<AlignmentOperation> {
+        let mut ops = Vec::new();
+        for op in self.0.iter() {
+            let (op, len) = match op {
+                CsOp::Match(n) => (AlignmentOperation::Match, *n),
+                CsOp::Identical(bases) => (AlignmentOperation::Match, bases.len()),
+                CsOp::Subst(_, _) => (AlignmentOperation::Subst, 1),
+                CsOp::Ins(bases) => (AlignmentOperation::Ins, bases.len()),
+                CsOp::Del(bases) => (AlignmentOperation::Del, bases.len()),
+                CsOp::Intron { len, .. } => (AlignmentOperation::RefSkip, *len),
+            };
+            ops.extend(std::iter::repeat(op).take(len));
+        }
+        ops
+    }
+
+    /// Rebuild the aligned reference bases, given the aligned part of the query.
+    /// The query is only needed for identical stretches in the short form. Bases
+    /// taken from the `cs` tag are returned as they appear there. The interior of
+    /// introns is not covered by the tag and is filled with `N`.
+    pub fn reference(&self, query: TextSlice) -> Result<Vec<u8>, CsError> {
+        let mut reference = Vec::new();
+        let mut x_i = 0;
+        let mut consume = |len: usize| {
+            x_i += len;
+            if x_i > query.len() {
+                Err(CsError::QueryTooShort)
+            } else {
+                Ok(&query[x_i - len..x_i])
+            }
+        };
+        for op in self.0.iter() {
+            match op {
+                CsOp::Match(n) => reference.extend_from_slice(consume(*n)?),
+                CsOp::Identical(bases) => {
+                    consume(bases.len())?;
+                    reference.extend_from_slice(bases);
+                }
+                CsOp::Subst(r, _) => {
+                    consume(1)?;
+                    reference.push(*r);
+                }
+                CsOp::Ins(bases) => {
+                    consume(bases.len())?;
+                }
+                CsOp::Del(bases) => reference.extend_from_slice(bases),
+                CsOp::Intron {
+                    donor,
+                    len,
+                    acceptor,
+                } => {
+                    reference.extend_from_slice(donor);
+                    reference.extend(std::iter::repeat(b'N').take(len - 4));
+                    reference.extend_from_slice(acceptor);
+                }
+            }
+        }
+        Ok(reference)
+    }
+}
+
+impl Display for Cs {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        for op in self.0.iter() {
+            match op {
+                CsOp::Match(n) => write!(f, ":{}", n)?,
+                CsOp::Identical(bases) => write!(f, "={}", String::from_utf8_lossy(bases))?,
+                CsOp::Subst(r, q) => write!(f, "*{}{}", *r as char, *q as char)?,
+                CsOp::Ins(bases) => write!(f, "+{}", String::from_utf8_lossy(bases))?,
+                CsOp::Del(bases) => write!(f, "-{}", String::from_utf8_lossy(bases))?,
+                CsOp::Intron {
+                    donor,
+                    len,
+                    acceptor,
+                } => write!(
+                    f,
+                    "~{}{}{}",
+                    String::from_utf8_lossy(donor),
+                    len,
+                    String::from_utf8_lossy(acceptor)
+                )?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Cs {
+    type Err = CsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.as_bytes();
+        let mut ops = Vec::new();
+        let mut i = 0;
+
+        // length of the run of bytes matching the predicate, starting at `from`
+        let run = |from: usize, pred: fn(&u8) -> bool| {
+            s[from..]
+                .iter()
+                .position(|c| !pred(c))
+                .unwrap_or(s.len() - from)
+        };
+        let number = |from: usize, len: usize| {
+            std::str::from_utf8(&s[from..from + len])
+                .unwrap()
+                .parse::<usize>()
+        };
+
+        while i < s.len() {
+            let c = s[i];
+            i += 1;
+            match c {
+                b':' => {
+                    let len = run(i, u8::is_ascii_digit);
+                    if len == 0 {
+                        return Err(CsError::Empty(':'));
+                    }
+                    ops.push(CsOp::Match(number(i, len)?));
+                    i += len;
+                }
+                b'=' | b'+' | b'-' => {
+                    let len = run(i, u8::is_ascii_alphabetic);
+                    if len == 0 {
+                        return Err(CsError::Empty(c as char));
+                    }
+                    let bases = s[i..i + len].to_vec();
+                    ops.push(match c {
+                        b'=' => CsOp::Identical(bases),
+                        b'+' => CsOp::Ins(bases),
+                        _ => CsOp::Del(bases),
+                    });
+                    i += len;
+                }
+                b'*' => {
+                    if run(i, u8::is_ascii_alphabetic) != 2 {
+                        return Err(CsError::InvalidSubst);
+                    }
+                    ops.push(CsOp::Subst(s[i], s[i + 1]));
+                    i += 2;
+                }
+                b'~' => {
+                    let digits = if run(i, u8::is_ascii_alphabetic) == 2 {
+                        run(i + 2, u8::is_ascii_digit)
+                    } else {
+                        0
+                    };
+                    if digits == 0 || run(i + 2 + digits, u8::is_ascii_alphabetic) != 2 {
+                        return Err(CsError::InvalidIntron);
+                    }
+                    let len = number(i + 2, digits)?;
+                    if len < 4 {
+                        return Err(CsError::InvalidIntron);
+                    }
+                    ops.push(CsOp::Intron {
+                        donor: [s[i], s[i + 1]],
+                        len,
+                        acceptor: [s[i + 2 + digits], s[i + 3 + digits]],
+                    });
+                    i += digits + 4;
+                }
+                _ => return Err(CsError::InvalidChar(c as char)),
+            }
+        }
+        Ok(Cs(ops))
+    }
+}
+
+/// Errors that arise when parsing or interpreting a `cs` tag.
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum CsError {
+    #[error("invalid character in cs tag: {0:?}")]
+    InvalidChar(char),
+    #[error("cs operation {0:?} is not followed by a length or bases")]
+    Empty(char),
+    #[error("substitution in cs tag has to consist of exactly two bases")]
+    InvalidSubst,
+    #[error("invalid intron in cs tag")]
+    InvalidIntron,
+    #[error("error parsing length in cs tag")]
+    ParseInt(#[from] std::num::ParseIntError),
+    #[error("query sequence is shorter than described by the cs tag")]
+    QueryTooShort,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alignment::AlignmentMode;
+    use crate::alignment::AlignmentOperation::*;
+
+    #[test]
+    fn test_cs_tag() {
+        let x = b"GGACGTTCGT";
+        let y = b"TTTACCAAGGTA";
+        let alignment = Alignment {
+            score: 0,
+            xstart: 2,
+            ystart: 3,
+            xend: 10,
+            yend: 11,
+            ylen: 12,
+            xlen: 10,
+            operations: vec![Match, Match, Subst, Subst, Del, Match, Ins, Match, Match],
+            mode: AlignmentMode::Local,
+        };
+        let short = alignment.cs_tag(x, y, false);
+        assert_eq!(short, ":2*cg*at-a*gt+c:2");
+        let long = alignment.cs_tag(x, y, true);
+        assert_eq!(long, "=AC*cg*at-a*gt+c=GT");
+
+        for cs in [short, long] {
+            let cs: Cs = cs.parse().unwrap();
+            assert_eq!(
+                cs.operations(),
+                [Match, Match, Subst, Subst, Del, Subst, Ins, Match, Match]
+            );
+            assert_eq!(
+                cs.reference(&x[2..]).unwrap().to_ascii_uppercase(),
+                &y[3..11]
+            );
+        }
+    }
+
+    #[test]
+    fn test_cs_tag_spliced() {
+        let alignment = Alignment::from_cigar("2M6N1M2N1M", 0, 4, 12).unwrap();
+        let cs = alignment.cs_tag(b"ACGT", b"ACGTAAAGGCCT", false);
+        assert_eq!(cs, ":2~gt6ag:1-cc:1");
+        let cs: Cs = cs.parse().unwrap();
+        assert_eq!(cs.operations()[2..8], [RefSkip; 6]);
+    }
+
+    #[test]
+    fn test_parse() {
+        let cs: Cs = ":3~gt12ag:2".parse().unwrap();
+        assert_eq!(
+            cs.0[1],
+            CsOp::Intron {
+                donor: *b"gt",
+                len: 12,
+                acceptor: *b"ag"
+            }
+        );
+        assert_eq!(cs.to_string(), ":3~gt12ag:2");
+        assert_eq!(cs.operations().len(), 17);
+        assert_eq!(cs.reference(b"ACGTA").unwrap(), b"ACGgtNNNNNNNNagTA");
+        assert_eq!(cs.reference(b"ACGT"), Err(CsError::QueryTooShort));
+
+        assert_eq!(":3*a:2".parse::<Cs>(), Err(CsError::InvalidSubst));
+        assert_eq!(":3+:2".parse::<Cs>(), Err(CsError::Empty('+')));
+        assert_eq!(":".parse::<Cs>(), Err(CsError::Empty(':')));
+        assert_eq!(":3~gt12:2".parse::<Cs>(), Err(CsError::InvalidIntron));
+        assert_eq!(":3/a".parse::<Cs>(), Err(CsError::InvalidChar('/')));
+    }
+}
@@ -0,0 +1,173 @@
+//! Conversion between alignments and pairs of gapped sequences, as e.g. written by
+//! EMBOSS or found in test fixtures (`ACG-T` / `AC-GT`).
+
+use thiserror::Error;
+
+use super::{Alignment, AlignmentMode, AlignmentOperation, TextSlice};
+
+pub(super) fn is_gap(c: u8) -> bool {
+    c == b'-' || c == b'.'
+}
+
+// The operation of an alignment column with the bases (or gaps) `a` of x and `b`
+// of y. Aligned bases are compared case-insensitively, columns with two gaps have no
+// operation.
+pub(super) fn column_operation(a: u8, b: u8) -> Option<AlignmentOperation> {
+    match (is_gap(a), is_gap(b)) {
+        (false, false) if a.eq_ignore_ascii_case(&b) => Some(AlignmentOperation::Match),
+        (false, false) => Some(AlignmentOperation::Subst),
+        (false, true) => Some(AlignmentOperation::Ins),
+        (true, false) => Some(AlignmentOperation::Del),
+        (true, true) => None,
+    }
+}
+
+impl Alignment {
+    /// Construct an alignment from the gapped sequences x and y, which have to be of
+    /// the same length. Both `-` and `.` denote gaps. Aligned bases are compared
+    /// case-insensitively to distinguish matches from substitutions, and columns
+    /// with gaps in both sequences are skipped.
+    ///
+    /// The gapped sequences are expected to cover x and y completely. In `Global`
+    /// mode, all columns are aligned. In `Semiglobal` mode, leading and trailing
+    /// gaps in x are taken as clipped parts of y, and in `Local` and `Custom` mode
+    /// leading and trailing gaps in either sequence are taken as clipped. The score
+    /// is set to zero.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bio_types::alignment::{Alignment,AlignmentMode};
+    /// use bio_types::alignment::AlignmentOperation::*;
+    /// let alignment = Alignment::from_gapped(b"--ACG-T", b"TTAC-GA", AlignmentMode::Local).unwrap();
+    /// assert_eq!(alignment.operations, [Match, Match, Ins, Del, Subst]);
+    /// assert_eq!((alignment.xstart, alignment.xend, alignment.xlen), (0, 4, 4));
+    /// assert_eq!((alignment.ystart, alignment.yend, alignment.ylen), (2, 6, 6));
+    /// ```
+    pub fn from_gapped(
+        x: TextSlice,
+        y: TextSlice,
+        mode: AlignmentMode,
+    ) -> Result<Alignment, GappedError> {
+        if x.len() != y.len() {
+            return Err(GappedError::LengthMismatch {
+                x: x.len(),
+                y: y.len(),
+            });
+        }
+        let operations: Vec<_> = x
+            .iter()
+            .zip(y.iter())
+            .filter_map(|(&a, &b)| column_operation(a, b))
+            .collect();
+
+        // whether the operation is an end gap that is taken as clipping
+        let clipped = |op: &AlignmentOperation| match mode {
+            AlignmentMode::Global => false,
+            AlignmentMode::Semiglobal => *op == AlignmentOperation::Del,
+            AlignmentMode::Local | AlignmentMode::Custom => {
+                matches!(op, AlignmentOperation::Ins | AlignmentOperation::Del)
+            }
+        };
+        let first = operations
+            .iter()
+            .position(|op| !clipped(op))
+            .unwrap_or(operations.len());
+        let last = operations
+            .iter()
+            .rposition(|op| !clipped(op))
+            .map_or(first, |i| i + 1);
+        let xstart = operations[..first]
+            .iter()
+            .filter(|op| **op == AlignmentOperation::Ins)
+            .count();
+        let ystart = first - xstart;
+
+        let bases = |seq: TextSlice| seq.iter().filter(|&&c| !is_gap(c)).count();
+        let sequences = Alignment {
+            xlen: bases(x),
+            ylen: bases(y),
+            ..Default::default()
+        };
+        Ok(sequences.with_aligned_operations(
+            operations[first..last].to_vec(),
+            xstart,
+            ystart,
+            mode,
+            0,
+        ))
+    }
+
+    /// Return the gapped sequences of the alignment, given x and y, with `-` for gaps.
+    /// As in [`pretty`](#method.pretty), the clipped parts of x and y are included,
+    /// aligned against gaps. This is the inverse of [`from_gapped`](#method.from_gapped)
+    /// for valid alignments that neither start nor end with a gap (except in `Global`
+    /// mode) and that do not contain reference skips.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bio_types::alignment::{Alignment,AlignmentMode};
+    /// use bio_types::alignment::AlignmentOperation::*;
+    /// let alignment = Alignment::from_cigar("2=1I1D1X", 2, 4, 6).unwrap();
+    /// let (x, y) = alignment.to_gapped(b"ACGT", b"TTACGA");
+    /// assert_eq!((&x[..], &y[..]), (&b"--ACG-T"[..], &b"TTAC-GA"[..]));
+    /// ```
+    pub fn to_gapped(&self, x: TextSlice, y: TextSlice) -> (Vec<u8>, Vec<u8>) {
+        self.columns(x, y)
+            .iter()
+            .map(|col| {
+                (
+                    col.x.map_or(b'-', |cell| cell.base),
+                    col.y.map_or(b'-', |cell| cell.base),
+                )
+            })
+            .unzip()
+    }
+}
+
+/// Errors that arise when constructing an alignment from gapped sequences.
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum GappedError {
+    #[error("gapped sequences have different lengths ({x} and {y})")]
+    LengthMismatch { x: usize, y: usize },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alignment::AlignmentOperation::*;
+
+    #[test]
+    fn test_from_to_gapped() {
+        let (x, y) = (b"ACGTTAC", b"GGACTTCAT");
+        let (x_gapped, y_gapped) = (b"--ACGTTAC-", b"GGAC-TTCAT");
+        for mode in [
+            AlignmentMode::Global,
+            AlignmentMode::Semiglobal,
+            AlignmentMode::Local,
+            AlignmentMode::Custom,
+        ] {
+            let alignment = Alignment::from_gapped(x_gapped, y_gapped, mode).unwrap();
+            assert_eq!(alignment.validate(), Ok(()));
+            assert_eq!(
+                alignment.to_gapped(x, y),
+                (x_gapped.to_vec(), y_gapped.to_vec())
+            );
+        }
+
+        let alignment =
+            Alignment::from_gapped(x_gapped, y_gapped, AlignmentMode::Semiglobal).unwrap();
+        assert_eq!(
+            alignment.operations,
+            [Match, Match, Ins, Match, Match, Subst, Subst]
+        );
+        assert_eq!((alignment.ystart, alignment.yend), (2, 8));
+        let alignment = Alignment::from_gapped(b"A-c", b"A.C", AlignmentMode::Global).unwrap();
+        assert_eq!(alignment.operations, [Match, Match]);
+        assert_eq!(
+            Alignment::from_gapped(b"AC", b"A", AlignmentMode::Global),
+            Err(GappedError::LengthMismatch { x: 2, y: 1 })
+        );
+    }
+}
@@ -0,0 +1,349 @@
+//! Generation and interpretation of the SAM `MD` tag, which records the reference
+//! bases at mismatching and deleted positions of an alignment.
+
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+use thiserror::Error;
+
+use super::{Alignment, AlignmentOperation, Cigar, CigarOp, TextSlice};
+
+impl Alignment {
+    /// Calculate the SAM `MD` tag of the alignment, given the query sequence x and
+    /// the reference sequence y. Aligned positions are reported as mismatches if the
+    /// (case-insensitive) bases differ, irrespective of whether they are marked as
+    /// `Match` or `Subst`. Reference bases are reported in upper case.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bio_types::alignment::{Alignment,AlignmentMode};
+    /// use bio_types::alignment::AlignmentOperation::*;
+    /// let x = b"ACGTTACGA";
+    /// let y = b"ACGATACCCGA";
+    /// let alignment = Alignment {
+    ///     score: 0,
+    ///     xstart: 0,
+    ///     ystart: 0,
+    ///     xend: 9,
+    ///     yend: 11,
+    ///     ylen: 11,
+    ///     xlen: 9,
+    ///     operations: vec![Match, Match, Match, Subst, Match, Match, Match, Del, Del, Match, Match],
+    ///     mode: AlignmentMode::Global,
+    /// };
+    /// assert_eq!(alignment.md_tag(x, y), "3A3^CC2");
+    /// ```
+    pub fn md_tag(&self, x: TextSlice, y: TextSlice) -> String {
+        let mut md = String::new();
+        let mut run = 0;
+        let mut in_del = false;
+        for (x_i, y_i, op) in self.positioned_operations() {
+            match op {
+                AlignmentOperation::Match | AlignmentOperation::Subst => {
+                    in_del = false;
+                    if x[x_i].eq_ignore_ascii_case(&y[y_i]) {
+                        run += 1;
+                    } else {
+                        md.push_str(&run.to_string());
+                        md.push(y[y_i].to_ascii_uppercase() as char);
+                        run = 0;
+                    }
+                }
+                AlignmentOperation::Del => {
+                    if !in_del {
+                        md.push_str(&run.to_string());
+                        md.push('^');
+                        run = 0;
+                        in_del = true;
+                    }
+                    md.push(y[y_i].to_ascii_uppercase() as char);
+                }
+                AlignmentOperation::Ins | AlignmentOperation::RefSkip => in_del = false,
+                _ => {}
+            }
+        }
+        md.push_str(&run.to_string());
+        md
+    }
+}
+
+/// An element of an `MD` tag.
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub enum MdOp {
+    /// A number of matching bases
+    Match(usize),
+    /// A mismatch, with the reference base
+    Mismatch(u8),
+    /// A deletion, with the deleted reference bases
+    Deletion(Vec<u8>),
+}
+
+/// A parsed `MD` tag.
+///
+/// ```
+/// use bio_types::alignment::md::{Md, MdOp};
+/// let md: Md = "3A0C^GT10".parse().unwrap();
+/// assert_eq!(
+///     md.0,
+///     [
+///         MdOp::Match(3),
+///         MdOp::Mismatch(b'A'),
+///         MdOp::Match(0),
+///         MdOp::Mismatch(b'C'),
+///         MdOp::Deletion(b"GT".to_vec()),
+///         MdOp::Match(10)
+///     ]
+/// );
+/// assert_eq!(md.to_string(), "3A0C^GT10");
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Default)]
+pub struct Md(pub Vec<MdOp>);
+
+impl Md {
+    /// Rebuild the reference bases spanned by an aligned read from its CIGAR and
+    /// `MD` tag, without access to the reference sequence. `read` is the read
+    /// sequence as stored in SAM records, i.e. including soft clipped bases but not
+    /// hard clipped ones. Bases skipped by `N` operations are not covered by the
+    /// `MD` tag and are filled with `N`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bio_types::alignment::Cigar;
+    /// use bio_types::alignment::md::Md;
+    /// let cigar: Cigar = "2S4M1I2M2D2M".parse().unwrap();
+    /// let md: Md = "2G3^TA2".parse().unwrap();
+    /// let reference = md.reference(&cigar, b"TTACCATGCAC").unwrap();
+    /// assert_eq!(reference, b"ACGAGCTAAC");
+    /// ```
+    pub fn reference(&self, cigar: &Cigar, read: TextSlice) -> Result<Vec<u8>, MdError> {
+        let mut reference = Vec::with_capacity(cigar.reference_len());
+        let mut cursor = MdCursor {
+            ops: &self.0,
+            idx: 0,
+            used: 0,
+        };
+        let mut x_i = 0;
+
+        for op in cigar.iter() {
+            let len = op.len() as usize;
+            match op {
+                CigarOp::Match(_) | CigarOp::Equal(_) | CigarOp::Diff(_) => {
+                    if x_i + len > read.len() {
+                        return Err(MdError::ReadTooShort);
+                    }
+                    for &base in &read[x_i..x_i + len] {
+                        reference.push(cursor.aligned()?.unwrap_or(base));
+                    }
+                    x_i += len;
+                }
+                CigarOp::Del(_) => {
+                    for _ in 0..len {
+                        reference.push(cursor.deleted()?);
+                    }
+                }
+                CigarOp::RefSkip(_) => reference.extend(std::iter::repeat(b'N').take(len)),
+                CigarOp::Ins(_) | CigarOp::SoftClip(_) => x_i += len,
+                CigarOp::HardClip(_) | CigarOp::Pad(_) => {}
+            }
+        }
+
+        if cursor.is_done() {
+            Ok(reference)
+        } else {
+            Err(MdError::CigarMismatch)
+        }
+    }
+}
+
+// Walks over the bases described by an MD tag.
+struct MdCursor<'a> {
+    ops: &'a [MdOp],
+    idx: usize,
+    used: usize,
+}
+
+impl MdCursor<'_> {
+    // Skip exhausted runs of matches.
+    fn skip_matches(&mut self) {
+        while let Some(MdOp::Match(n)) = self.ops.get(self.idx) {
+            if self.used < *n {
+                break;
+            }
+            self.idx += 1;
+            self.used = 0;
+        }
+    }
+
+    // Consume an aligned base, returning the reference base in case of a mismatch.
+    fn aligned(&mut self) -> Result<Option<u8>, MdError> {
+        self.skip_matches();
+        match self.ops.get(self.idx) {
+            Some(MdOp::Match(_)) => {
+                self.used += 1;
+                Ok(None)
+            }
+            Some(MdOp::Mismatch(base)) => {
+                self.idx += 1;
+                Ok(Some(*base))
+            }
+            _ => Err(MdError::CigarMismatch),
+        }
+    }
+
+    // Consume a deleted base, returning the reference base.
+    fn deleted(&mut self) -> Result<u8, MdError> {
+        self.skip_matches();
+        match self.ops.get(self.idx) {
+            Some(MdOp::Deletion(bases)) => {
+                let base = bases[self.used];
+                self.used += 1;
+                if self.used == bases.len() {
+                    self.idx += 1;
+                    self.used = 0;
+                }
+                Ok(base)
+            }
+            _ => Err(MdError::CigarMismatch),
+        }
+    }
+
+    fn is_done(&mut self) -> bool {
+        self.skip_matches();
+        self.idx == self.ops.len()
+    }
+}
+
+impl Display for Md {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        for op in self.0.iter() {
+            match op {
+                MdOp::Match(n) => write!(f, "{}", n)?,
+                MdOp::Mismatch(base) => write!(f, "{}", *base as char)?,
+                MdOp::Deletion(bases) => write!(f, "^{}", String::from_utf8_lossy(bases))?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Md {
+    type Err = MdError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut ops = Vec::new();
+        let mut rest = s.as_bytes();
+        while let Some(&c) = rest.first() {
+            if c.is_ascii_digit() {
+                let digits = rest
+                    .iter()
+                    .position(|c| !c.is_ascii_digit())
+                    .unwrap_or(rest.len());
+                let n = std::str::from_utf8(&rest[..digits])
+                    .unwrap()
+                    .parse::<usize>()?;
+                ops.push(MdOp::Match(n));
+                rest = &rest[digits..];
+            } else if c == b'^' {
+                let bases = rest[1..]
+                    .iter()
+                    .position(|c| !c.is_ascii_alphabetic())
+                    .unwrap_or(rest.len() - 1);
+                if bases == 0 {
+                    return Err(MdError::EmptyDeletion);
+                }
+                ops.push(MdOp::Deletion(rest[1..=bases].to_vec()));
+                rest = &rest[bases + 1..];
+            } else if c.is_ascii_alphabetic() {
+                ops.push(MdOp::Mismatch(c));
+                rest = &rest[1..];
+            } else {
+                return Err(MdError::InvalidChar(c as char));
+            }
+        }
+        Ok(Md(ops))
+    }
+}
+
+/// Errors that arise when parsing or interpreting an `MD` tag.
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum MdError {
+    #[error("invalid character in MD tag: {0:?}")]
+    InvalidChar(char),
+    #[error("deletion in MD tag without reference bases")]
+    EmptyDeletion,
+    #[error("error parsing number of matches in MD tag")]
+    ParseInt(#[from] std::num::ParseIntError),
+    #[error("MD tag does not agree with the CIGAR")]
+    CigarMismatch,
+    #[error("read sequence is shorter than described by the CIGAR")]
+    ReadTooShort,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alignment::AlignmentMode;
+    use crate::alignment::AlignmentOperation::*;
+
+    #[test]
+    fn test_md_tag() {
+        // adjacent mismatches, mismatch after deletion and soft clips
+        let x = b"GGACGTTCGT";
+        let y = b"TTTACCAAGGTA";
+        let alignment = Alignment {
+            score: 0,
+            xstart: 2,
+            ystart: 3,
+            xend: 10,
+            yend: 11,
+            ylen: 12,
+            xlen: 10,
+            operations: vec![Match, Match, Subst, Subst, Del, Match, Ins, Match, Match],
+            mode: AlignmentMode::Local,
+        };
+        assert_eq!(alignment.md_tag(x, y), "2C0A0^A0G2");
+        let cigar = alignment.to_cigar(false).unwrap();
+        let md: Md = alignment.md_tag(x, y).parse().unwrap();
+        assert_eq!(md.reference(&cigar, x).unwrap(), &y[3..11]);
+
+        // custom mode alignment from a CIGAR with M operations
+        let alignment = Alignment::from_cigar("1H3M1D3M", 2, 7, 10).unwrap();
+        assert_eq!(alignment.md_tag(b"AACTCCA", b"GGAGTGCTAG"), "1G1^G1T1");
+
+        // deletions separated by a reference skip are separate deletions
+        let (x, y) = (b"GT", b"GANNNNCT");
+        let alignment = Alignment::from_cigar("1M1D4N1D1M", 0, 2, 8).unwrap();
+        assert_eq!(alignment.md_tag(x, y), "1^A0^C1");
+        let cigar = alignment.to_cigar(false).unwrap();
+        let md: Md = alignment.md_tag(x, y).parse().unwrap();
+        assert_eq!(md.reference(&cigar, x).unwrap(), y);
+    }
+
+    #[test]
+    fn test_reference() {
+        let cigar: Cigar = "3M100N3M".parse().unwrap();
+        let md: Md = "1C4".parse().unwrap();
+        let reference = md.reference(&cigar, b"AGCGTA").unwrap();
+        assert_eq!(&reference[..3], b"ACC");
+        assert_eq!(&reference[103..], b"GTA");
+        assert_eq!(reference.len(), 106);
+
+        let cigar: Cigar = "4M".parse().unwrap();
+        assert_eq!(
+            "5".parse::<Md>().unwrap().reference(&cigar, b"ACGT"),
+            Err(MdError::CigarMismatch)
+        );
+        assert_eq!(
+            "2^A1".parse::<Md>().unwrap().reference(&cigar, b"ACGT"),
+            Err(MdError::CigarMismatch)
+        );
+        assert_eq!(
+            "4".parse::<Md>().unwrap().reference(&cigar, b"ACG"),
+            Err(MdError::ReadTooShort)
+        );
+        assert_eq!("3^1".parse::<Md>(), Err(MdError::EmptyDeletion));
+        assert_eq!("3-1".parse::<Md>(), Err(MdError::InvalidChar('-')));
+    }
+}
@@ -0,0 +1,826 @@
+// Copyright 2014-2015 Johannes Köster, Vadim Nazarov, Patrick Marks
+// Licensed under the MIT license (http://opensource.org/licenses/MIT)
+// This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Types for representing pairwise sequence alignments
+
+#[cfg(feature = "clap")]
+use clap::ValueEnum;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+pub mod cigar;
+pub mod clip;
+pub mod cs;
+pub mod gapped;
+pub mod md;
+pub mod multiple;
+pub mod paf;
+pub mod projection;
+pub mod render;
+pub mod rle;
+pub mod scoring;
+pub mod slice;
+pub mod spliced;
+pub mod stats;
+pub mod validate;
+pub mod variants;
+
+pub use self::cigar::{Cigar, CigarError, CigarOp};
+pub use self::gapped::GappedError;
+pub use self::projection::Projection;
+pub use self::slice::ConcatError;
+pub use self::stats::AlignmentStats;
+pub use self::validate::{AlignmentBuilder, AlignmentError};
+
+pub type TextSlice<'a> = &'a [u8];
+
+/// Alignment operations supported are match, substitution, insertion, deletion,
+/// reference skip and clipping. A reference skip (`RefSkip`) consumes a reference
+/// base like a deletion, but denotes a skipped region such as an intron in a spliced
+/// alignment (`N` in CIGAR). Clipping is a special boundary condition where you are allowed
+/// to clip off the beginning/end of the sequence for a fixed clip penalty. The
+/// clip penalty could be different for the two sequences x and y, and the
+/// clipping operations on both are distinguishable (Xclip and Yclip). The usize
+/// value associated with the clipping operations are the lengths clipped. In case
+/// of standard modes like Global, Semi-Global and Local alignment, the clip operations
+/// are filtered out
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Eq, PartialEq, Debug, Copy, Clone, Hash)]
+pub enum AlignmentOperation {
+    Match,
+    Subst,
+    Del,
+    Ins,
+    RefSkip,
+    Xclip(usize),
+    Yclip(usize),
+}
+
+/// The modes of alignment supported by the aligner include standard modes such as
+/// Global, Semi-Global and Local alignment. In addition to this, user can also invoke
+/// the custom mode. In the custom mode, users can explicitly specify the clipping penalties
+/// for prefix and suffix of strings 'x' and 'y' independently. Under the hood the standard
+/// modes are implemented as special cases of the custom mode with the clipping penalties
+/// appropriately set.
+///
+/// The default alignment mode is Global.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "clap", derive(ValueEnum))]
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Default)]
+pub enum AlignmentMode {
+    Local,
+    Semiglobal,
+    #[default]
+    Global,
+    Custom,
+}
+
+/// We consider alignment between two sequences x and  y. x is the query or read sequence
+/// and y is the reference or template sequence. An alignment, consisting of a score,
+/// the start and end position of the alignment on sequence x and sequence y, the
+/// lengths of sequences x and y, and the alignment edit operations. The start position
+/// and end position of the alignment does not include the clipped regions. The length
+/// of clipped regions are already encapsulated in the Alignment Operation.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Eq, PartialEq, Clone, Default)]
+pub struct Alignment {
+    /// Smith-Waterman alignment score
+    pub score: i32,
+
+    /// Start position of alignment in reference
+    pub ystart: usize,
+
+    /// Start position of alignment in query
+    pub xstart: usize,
+
+    /// End position of alignment in reference
+    pub yend: usize,
+
+    /// End position of alignment in query
+    pub xend: usize,
+
+    /// Length of the reference sequence
+    pub ylen: usize,
+
+    /// Length of the query sequence
+    pub xlen: usize,
+
+    /// Vector of alignment operations
+    pub operations: Vec<AlignmentOperation>,
+    pub mode: AlignmentMode,
+}
+
+impl Alignment {
+    /// Construct an alignment from a CIGAR string, as found in SAM or PAF records.
+    /// `ystart` is the 0-based position of the first aligned reference base, `xlen`
+    /// and `ylen` are the lengths of the query and the reference sequence.
+    ///
+    /// The resulting alignment is in `Custom` mode, i.e. soft (`S`) and hard (`H`)
+    /// clips become `Xclip` operations and the unaligned reference prefix and suffix
+    /// become `Yclip` operations. Since the sequences are not known, `M` is
+    /// interpreted as a `Match`. Reference skips (`N`) become `RefSkip` operations
+    /// and padding (`P`) is ignored. The score of the alignment is set to zero.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bio_types::alignment::{Alignment,AlignmentMode};
+    /// use bio_types::alignment::AlignmentOperation::*;
+    /// let alignment = Alignment::from_cigar("3S3=1X2I2D1S", 5, 10, 20).unwrap();
+    /// assert_eq!(alignment.xstart, 3);
+    /// assert_eq!(alignment.xend, 9);
+    /// assert_eq!(alignment.ystart, 5);
+    /// assert_eq!(alignment.yend, 11);
+    /// assert_eq!(alignment.mode, AlignmentMode::Custom);
+    /// assert_eq!(
+    ///     alignment.operations,
+    ///     [Yclip(5), Xclip(3), Match, Match, Match, Subst, Ins, Ins, Del, Del, Xclip(1), Yclip(9)]
+    /// );
+    /// ```
+    pub fn from_cigar(
+        cigar: &str,
+        ystart: usize,
+        xlen: usize,
+        ylen: usize,
+    ) -> Result<Self, CigarError> {
+        cigar.parse::<Cigar>()?.to_alignment(ystart, xlen, ylen)
+    }
+
+    /// Calculate the cigar string from the alignment struct. x is the target string.
+    /// The unaligned prefix and suffix of x (i.e. before `xstart` and after `xend`)
+    /// are reported as soft clips, or as hard clips if `hard_clip` is set. This works
+    /// the same for all alignment modes.
+    ///
+    /// An error is returned if the operations do not add up to the aligned range
+    /// `xstart..xend` of x, or if that range exceeds `xlen`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bio_types::alignment::{Alignment,AlignmentMode};
+    /// use bio_types::alignment::AlignmentOperation::{Match, Subst, Ins, Del};
+    /// let alignment = Alignment {
+    ///     score: 5,
+    ///     xstart: 3,
+    ///     ystart: 0,
+    ///     xend: 9,
+    ///     yend: 10,
+    ///     ylen: 10,
+    ///     xlen: 10,
+    ///     operations: vec![Match, Match, Match, Subst, Ins, Ins, Del, Del],
+    ///     mode: AlignmentMode::Semiglobal
+    /// };
+    /// assert_eq!(alignment.cigar(false).unwrap(), "3S3=1X2I2D1S");
+    /// ```
+    pub fn cigar(&self, hard_clip: bool) -> Result<String, CigarError> {
+        let cigar = self.to_cigar(hard_clip)?;
+        if cigar.is_empty() {
+            Ok(String::new())
+        } else {
+            Ok(cigar.to_string())
+        }
+    }
+
+    /// Calculate the typed CIGAR from the alignment struct, in the same way as
+    /// [`cigar`](#method.cigar).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bio_types::alignment::{Alignment,AlignmentMode,CigarOp};
+    /// use bio_types::alignment::AlignmentOperation::{Match, Subst, Ins, Del};
+    /// let alignment = Alignment {
+    ///     score: 5,
+    ///     xstart: 3,
+    ///     ystart: 0,
+    ///     xend: 9,
+    ///     yend: 10,
+    ///     ylen: 10,
+    ///     xlen: 10,
+    ///     operations: vec![Match, Match, Match, Subst, Ins, Ins, Del, Del],
+    ///     mode: AlignmentMode::Semiglobal
+    /// };
+    /// let cigar = alignment.to_cigar(true).unwrap();
+    /// assert_eq!(cigar[0], CigarOp::HardClip(3));
+    /// assert_eq!(cigar[1], CigarOp::Equal(3));
+    /// assert_eq!(cigar.query_len(), 10);
+    /// ```
+    pub fn to_cigar(&self, hard_clip: bool) -> Result<Cigar, CigarError> {
+        self.cigar_of_runs(self.runs(), hard_clip)
+    }
+
+    // Calculate the CIGAR from runs of operations, see `to_cigar`.
+    pub(crate) fn cigar_of_runs<I>(&self, runs: I, hard_clip: bool) -> Result<Cigar, CigarError>
+    where
+        I: Iterator<Item = (AlignmentOperation, usize)> + Clone,
+    {
+        let x_ops = runs
+            .clone()
+            .filter(|(op, _)| {
+                matches!(
+                    op,
+                    AlignmentOperation::Match | AlignmentOperation::Subst | AlignmentOperation::Ins
+                )
+            })
+            .map(|(_, len)| len)
+            .sum();
+        if self.xend > self.xlen {
+            return Err(CigarError::QueryOutOfBounds {
+                end: self.xend,
+                len: self.xlen,
+            });
+        }
+        if self.xstart + x_ops != self.xend {
+            return Err(CigarError::InconsistentQueryRange {
+                ops: x_ops,
+                xstart: self.xstart,
+                xend: self.xend,
+            });
+        }
+
+        let clip = |len: usize| -> Result<CigarOp, CigarError> {
+            let len = cigar::run_len(len)?;
+            Ok(if hard_clip {
+                CigarOp::HardClip(len)
+            } else {
+                CigarOp::SoftClip(len)
+            })
+        };
+
+        let mut aligned = Cigar::new();
+        for (op, len) in runs {
+            let len = cigar::run_len(len)?;
+            match op {
+                AlignmentOperation::Match => aligned.push(CigarOp::Equal(len)),
+                AlignmentOperation::Subst => aligned.push(CigarOp::Diff(len)),
+                AlignmentOperation::Del => aligned.push(CigarOp::Del(len)),
+                AlignmentOperation::RefSkip => aligned.push(CigarOp::RefSkip(len)),
+                AlignmentOperation::Ins => aligned.push(CigarOp::Ins(len)),
+                AlignmentOperation::Xclip(_) | AlignmentOperation::Yclip(_) => {}
+            }
+        }
+        let mut cigar = Cigar::new();
+        if aligned.is_empty() {
+            return Ok(cigar);
+        }
+        cigar.push(clip(self.xstart)?);
+        cigar.0.extend(aligned.0);
+        cigar.push(clip(self.xlen - self.xend)?);
+
+        Ok(cigar)
+    }
+
+    /// Return the pretty formatted alignment as a String. The string
+    /// contains sets of 3 lines of length 100. First line is for the
+    /// sequence x, second line is for the alignment operation and the
+    /// the third line is for the sequence y. A '-' in the sequence
+    /// indicates a blank (insertion/deletion). The operations follow
+    /// the following convention: '|' for a match, '\\' (a single backslash) for a mismatch,
+    /// '+' for an insertion, 'x' for a deletion, '.' for a reference skip and ' ' for
+    /// clipping.
+    /// Colored and HTML output is available via [`render`](#method.render).
+    ///
+    /// # Example
+    ///
+    /// If we align the strings "CCGTCCGGCAAGGG" and "AAAAACCGTTGACGGCCAA"
+    /// in various modes, we will get the following output:
+    ///
+    /// Semiglobal:
+    /// ```c
+    ///         CCGTCCGGCAAGGG
+    ///         ||||++++\\|\||
+    ///    AAAAACCGT----TGACGGCCAA
+    /// ```
+    ///
+    /// Local:
+    /// ```c
+    ///         CCGTCCGGCAAGGG
+    ///         ||||
+    ///    AAAAACCGT          TGACGGCCAA
+    /// ```
+    ///
+    /// Global:
+    /// ```c
+    ///    -----CCGT--CCGGCAAGGG
+    ///    xxxxx||||xx\||||\|++\
+    ///    AAAAACCGTTGACGGCCA--A
+    /// ```
+    ///
+    pub fn pretty(&self, x: TextSlice, y: TextSlice, ncol: usize) -> String {
+        self.render(x, y, ncol, &mut render::PlainRenderer)
+    }
+
+    /// Returns the optimal path in the alignment matrix
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bio_types::alignment::{Alignment,AlignmentMode};
+    /// use bio_types::alignment::AlignmentOperation::*;
+    /// let alignment = Alignment {
+    ///     score: 5,
+    ///     xstart: 3,
+    ///     ystart: 0,
+    ///     xend: 9,
+    ///     yend: 10,
+    ///     ylen: 10,
+    ///     xlen: 10,
+    ///     operations: vec![Match, Match, Match, Subst, Ins, Ins, Del, Del],
+    ///     mode: AlignmentMode::Semiglobal,
+    /// };
+    /// assert_eq!(alignment.path(),[
+    ///     (4, 5, Match),
+    ///     (5, 6, Match),
+    ///     (6, 7, Match),
+    ///     (7, 8, Subst),
+    ///     (8, 8, Ins),
+    ///     (9, 8, Ins),
+    ///     (9, 9, Del),
+    ///     (9, 10, Del)])
+    /// ```
+    pub fn path(&self) -> Vec<(usize, usize, AlignmentOperation)> {
+        self.path_of_runs(self.runs())
+    }
+
+    // Calculate the path from runs of operations, see `path`.
+    pub(crate) fn path_of_runs<I>(&self, runs: I) -> Vec<(usize, usize, AlignmentOperation)>
+    where
+        I: DoubleEndedIterator<Item = (AlignmentOperation, usize)>,
+    {
+        let mut path = Vec::new();
+        let mut runs = runs.rev().peekable();
+
+        if runs.peek().is_some() {
+            let last = match self.mode {
+                AlignmentMode::Custom => (self.xlen, self.ylen),
+                _ => (self.xend, self.yend),
+            };
+            let mut x_i = last.0;
+            let mut y_i = last.1;
+
+            // Process the alignment.
+            for (i, len) in runs {
+                for _ in 0..len {
+                    path.push((x_i, y_i, i));
+                    match i {
+                        AlignmentOperation::Match => {
+                            x_i -= 1;
+                            y_i -= 1;
+                        }
+                        AlignmentOperation::Subst => {
+                            x_i -= 1;
+                            y_i -= 1;
+                        }
+                        AlignmentOperation::Del | AlignmentOperation::RefSkip => {
+                            y_i -= 1;
+                        }
+                        AlignmentOperation::Ins => {
+                            x_i -= 1;
+                        }
+                        AlignmentOperation::Xclip(len) => {
+                            x_i -= len;
+                        }
+                        AlignmentOperation::Yclip(len) => {
+                            y_i -= len;
+                        }
+                    }
+                }
+            }
+        }
+        path.reverse();
+        path
+    }
+
+    /// Iterate over the alignment operations together with the positions in x and y
+    /// at which each operation starts. In the standard modes, the implicit prefix
+    /// clipping is skipped, in `Custom` mode it is given by the clip operations.
+    pub(crate) fn positioned_operations(
+        &self,
+    ) -> impl Iterator<Item = (usize, usize, AlignmentOperation)> + '_ {
+        self.positioned_runs(self.runs())
+    }
+
+    // Like `positioned_operations`, for runs of operations.
+    pub(crate) fn positioned_runs<I>(
+        &self,
+        runs: I,
+    ) -> impl Iterator<Item = (usize, usize, AlignmentOperation)>
+    where
+        I: Iterator<Item = (AlignmentOperation, usize)>,
+    {
+        let (mut x_i, mut y_i) = match self.mode {
+            AlignmentMode::Custom => (0, 0),
+            _ => (self.xstart, self.ystart),
+        };
+        runs.flat_map(|(op, len)| std::iter::repeat(op).take(len))
+            .map(move |op| {
+                let pos = (x_i, y_i, op);
+                match op {
+                    AlignmentOperation::Match | AlignmentOperation::Subst => {
+                        x_i += 1;
+                        y_i += 1;
+                    }
+                    AlignmentOperation::Del | AlignmentOperation::RefSkip => y_i += 1,
+                    AlignmentOperation::Ins => x_i += 1,
+                    AlignmentOperation::Xclip(len) => x_i += len,
+                    AlignmentOperation::Yclip(len) => y_i += len,
+                }
+                pos
+            })
+    }
+
+    // The operations as runs of length one.
+    fn runs(&self) -> impl DoubleEndedIterator<Item = (AlignmentOperation, usize)> + Clone + '_ {
+        self.operations.iter().map(|&op| (op, 1))
+    }
+
+    /// Reverse the alignment, yielding the alignment of the reversed x against the
+    /// reversed y. This is e.g. needed to express an alignment of a reverse
+    /// complemented read in terms of the forward strand of the read and the reverse
+    /// strand of the reference. Start and end positions are mirrored against the
+    /// sequence lengths and, in `Custom` mode, prefix and suffix clips swap places.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bio_types::alignment::{Alignment,AlignmentMode};
+    /// use bio_types::alignment::AlignmentOperation::*;
+    /// let alignment = Alignment::from_cigar("3S3=1X2I2D1S", 5, 10, 20).unwrap();
+    /// let reversed = alignment.reverse();
+    /// assert_eq!((reversed.xstart, reversed.xend), (1, 7));
+    /// assert_eq!((reversed.ystart, reversed.yend), (9, 15));
+    /// assert_eq!(reversed.cigar(false).unwrap(), "1S2D2I1X3=3S");
+    /// assert_eq!(reversed.reverse(), alignment);
+    /// ```
+    pub fn reverse(&self) -> Alignment {
+        let mut operations = self.operations.clone();
+        operations.reverse();
+        Alignment {
+            score: self.score,
+            ystart: self.ylen - self.yend,
+            xstart: self.xlen - self.xend,
+            yend: self.ylen - self.ystart,
+            xend: self.xlen - self.xstart,
+            ylen: self.ylen,
+            xlen: self.xlen,
+            operations,
+            mode: self.mode,
+        }
+    }
+
+    /// Transpose the alignment, i.e. swap the roles of x and y. Insertions become
+    /// deletions and vice versa, as do x and y clips. Reference skips have no
+    /// counterpart in x and become insertions.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bio_types::alignment::{Alignment,AlignmentMode};
+    /// use bio_types::alignment::AlignmentOperation::*;
+    /// let alignment = Alignment::from_cigar("3=1X2I2D", 5, 6, 20).unwrap();
+    /// let transposed = alignment.transpose();
+    /// assert_eq!((transposed.xstart, transposed.xend), (5, 11));
+    /// assert_eq!((transposed.ystart, transposed.yend), (0, 6));
+    /// assert_eq!(transposed.cigar(false).unwrap(), "5S3=1X2D2I9S");
+    /// assert_eq!(transposed.transpose(), alignment);
+    /// ```
+    pub fn transpose(&self) -> Alignment {
+        let operations = self
+            .operations
+            .iter()
+            .map(|op| match *op {
+                AlignmentOperation::Ins => AlignmentOperation::Del,
+                AlignmentOperation::Del | AlignmentOperation::RefSkip => AlignmentOperation::Ins,
+                AlignmentOperation::Xclip(len) => AlignmentOperation::Yclip(len),
+                AlignmentOperation::Yclip(len) => AlignmentOperation::Xclip(len),
+                op => op,
+            })
+            .collect();
+        Alignment {
+            score: self.score,
+            ystart: self.xstart,
+            xstart: self.ystart,
+            yend: self.xend,
+            xend: self.yend,
+            ylen: self.xlen,
+            xlen: self.ylen,
+            operations,
+            mode: self.mode,
+        }
+    }
+
+    /// Filter out Xclip and Yclip operations from the list of operations. Useful
+    /// when invoking the standard modes. The mode is left unchanged, so to convert a
+    /// `Custom` alignment use [`to_mode`](#method.to_mode) instead.
+    pub fn filter_clip_operations(&mut self) {
+        self.operations.retain(|x| {
+            !matches!(
+                x,
+                AlignmentOperation::Xclip(_) | AlignmentOperation::Yclip(_)
+            )
+        });
+    }
+
+    /// Number of bases in reference sequence that are aligned
+    pub fn y_aln_len(&self) -> usize {
+        self.yend - self.ystart
+    }
+
+    /// Number of bases in query sequence that are aigned
+    pub fn x_aln_len(&self) -> usize {
+        self.xend - self.xstart
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AlignmentOperation::*;
+    use super::*;
+
+    #[test]
+    fn test_cigar() {
+        let alignment = Alignment {
+            score: 5,
+            xstart: 3,
+            ystart: 0,
+            xend: 9,
+            yend: 10,
+            ylen: 10,
+            xlen: 10,
+            operations: vec![Match, Match, Match, Subst, Ins, Ins, Del, Del],
+            mode: AlignmentMode::Semiglobal,
+        };
+        assert_eq!(alignment.cigar(false).unwrap(), "3S3=1X2I2D1S");
+
+        let alignment = Alignment {
+            score: 5,
+            xstart: 0,
+            ystart: 5,
+            xend: 4,
+            yend: 10,
+            ylen: 10,
+            xlen: 5,
+            operations: vec![Yclip(5), Match, Subst, Subst, Ins, Del, Del, Xclip(1)],
+            mode: AlignmentMode::Custom,
+        };
+        assert_eq!(alignment.cigar(false).unwrap(), "1=2X1I2D1S");
+        assert_eq!(alignment.cigar(true).unwrap(), "1=2X1I2D1H");
+
+        let alignment = Alignment {
+            score: 5,
+            xstart: 0,
+            ystart: 5,
+            xend: 3,
+            yend: 8,
+            ylen: 10,
+            xlen: 3,
+            operations: vec![Yclip(5), Subst, Match, Subst, Yclip(2)],
+            mode: AlignmentMode::Custom,
+        };
+        assert_eq!(alignment.cigar(false).unwrap(), "1X1=1X");
+
+        let alignment = Alignment {
+            score: 5,
+            xstart: 0,
+            ystart: 5,
+            xend: 3,
+            yend: 8,
+            ylen: 10,
+            xlen: 3,
+            operations: vec![Subst, Match, Subst],
+            mode: AlignmentMode::Semiglobal,
+        };
+        assert_eq!(alignment.cigar(false).unwrap(), "1X1=1X");
+    }
+
+    #[test]
+    fn test_cigar_modes() {
+        let mut alignment = Alignment {
+            score: 2,
+            xstart: 0,
+            ystart: 0,
+            xend: 14,
+            yend: 19,
+            ylen: 19,
+            xlen: 14,
+            operations: vec![
+                Del, Del, Del, Del, Del, Match, Match, Match, Match, Del, Del, Subst, Match, Match,
+                Match, Match, Subst, Match, Ins, Ins, Subst,
+            ],
+            mode: AlignmentMode::Global,
+        };
+        assert_eq!(alignment.cigar(false).unwrap(), "5D4=2D1X4=1X1=2I1X");
+
+        alignment.operations.push(Ins);
+        assert_eq!(
+            alignment.cigar(false),
+            Err(CigarError::InconsistentQueryRange {
+                ops: 15,
+                xstart: 0,
+                xend: 14
+            })
+        );
+
+        let alignment = Alignment {
+            score: 4,
+            xstart: 0,
+            ystart: 5,
+            xend: 4,
+            yend: 9,
+            ylen: 19,
+            xlen: 14,
+            operations: vec![Match, Match, Match, Match],
+            mode: AlignmentMode::Local,
+        };
+        assert_eq!(alignment.cigar(false).unwrap(), "4=10S");
+        assert_eq!(alignment.cigar(true).unwrap(), "4=10H");
+
+        let alignment = Alignment {
+            score: 4,
+            xstart: 2,
+            ystart: 5,
+            xend: 6,
+            yend: 9,
+            ylen: 19,
+            xlen: 5,
+            operations: vec![Match, Match, Match, Match],
+            mode: AlignmentMode::Local,
+        };
+        assert_eq!(
+            alignment.cigar(false),
+            Err(CigarError::QueryOutOfBounds { end: 6, len: 5 })
+        );
+    }
+
+    #[test]
+    fn test_pretty() {
+        let alignment = Alignment {
+            score: 1,
+            xstart: 0,
+            ystart: 2,
+            xend: 3,
+            yend: 5,
+            ylen: 7,
+            xlen: 2,
+            operations: vec![Subst, Match, Match],
+            mode: AlignmentMode::Semiglobal,
+        };
+        let pretty = concat!("  GAT  \n", "  \\||  \n", "CTAATCC\n", "\n\n");
+        assert_eq!(alignment.pretty(b"GAT", b"CTAATCC", 100), pretty);
+        let alignment = Alignment {
+            score: 5,
+            xstart: 0,
+            ystart: 5,
+            xend: 4,
+            yend: 10,
+            ylen: 10,
+            xlen: 5,
+            operations: vec![Yclip(5), Match, Subst, Subst, Ins, Del, Del, Xclip(1)],
+            mode: AlignmentMode::Custom,
+        };
+        let pretty = "     AAAA--A\n     |\\\\+xx \nTTTTTTTT-TT \n\n\n";
+        assert_eq!(alignment.pretty(b"AAAAA", b"TTTTTTTTTT", 100), pretty);
+    }
+
+    #[test]
+    fn test_path() {
+        let alignment = Alignment {
+            score: 5,
+            xstart: 3,
+            ystart: 0,
+            xend: 9,
+            yend: 10,
+            ylen: 10,
+            xlen: 10,
+            operations: vec![Match, Match, Match, Subst, Ins, Ins, Del, Del],
+            mode: AlignmentMode::Semiglobal,
+        };
+        assert_eq!(
+            alignment.path(),
+            [
+                (4, 5, Match),
+                (5, 6, Match),
+                (6, 7, Match),
+                (7, 8, Subst),
+                (8, 8, Ins),
+                (9, 8, Ins),
+                (9, 9, Del),
+                (9, 10, Del)
+            ]
+        )
+    }
+
+    #[test]
+    fn test_reverse_transpose() {
+        let alignment = Alignment {
+            score: 5,
+            xstart: 1,
+            ystart: 2,
+            xend: 5,
+            yend: 6,
+            ylen: 8,
+            xlen: 6,
+            operations: vec![Match, Ins, Subst, Del, Match],
+            mode: AlignmentMode::Local,
+        };
+        let reversed = alignment.reverse();
+        assert_eq!(
+            reversed.path(),
+            [
+                (2, 3, Match),
+                (2, 4, Del),
+                (3, 5, Subst),
+                (4, 5, Ins),
+                (5, 6, Match)
+            ]
+        );
+        assert_eq!(reversed.cigar(false).unwrap(), "1S1=1D1X1I1=1S");
+
+        let transposed = alignment.transpose();
+        assert_eq!(
+            transposed.path(),
+            [
+                (3, 2, Match),
+                (3, 3, Del),
+                (4, 4, Subst),
+                (5, 4, Ins),
+                (6, 5, Match)
+            ]
+        );
+        assert_eq!(transposed.cigar(false).unwrap(), "2S1=1D1X1I1=2S");
+        assert_eq!(transposed.reverse().transpose(), reversed);
+    }
+
+    #[test]
+    fn test_from_cigar() {
+        let alignment = Alignment::from_cigar("2H1S4M1N2X1I1P1=3S", 2, 14, 12).unwrap();
+        assert_eq!(alignment.xstart, 3);
+        assert_eq!(alignment.xend, 11);
+        assert_eq!(alignment.ystart, 2);
+        assert_eq!(alignment.yend, 10);
+        assert_eq!(
+            alignment.operations,
+            [
+                Yclip(2),
+                Xclip(3),
+                Match,
+                Match,
+                Match,
+                Match,
+                RefSkip,
+                Subst,
+                Subst,
+                Ins,
+                Match,
+                Xclip(3),
+                Yclip(2)
+            ]
+        );
+        assert_eq!(alignment.x_aln_len(), 8);
+        assert_eq!(alignment.y_aln_len(), 8);
+        assert_eq!(alignment.path()[2], (4, 3, Match));
+        assert_eq!(alignment.cigar(false).unwrap(), "3S4=1N2X1I1=3S");
+
+        let alignment = Alignment::from_cigar("5=", 0, 5, 5).unwrap();
+        assert_eq!(alignment.operations, [Match; 5]);
+    }
+
+    #[test]
+    fn test_from_cigar_errors() {
+        assert_eq!(Alignment::from_cigar("", 0, 0, 0), Err(CigarError::Empty));
+        assert_eq!(
+            Alignment::from_cigar("5M3", 0, 5, 10),
+            Err(CigarError::MissingOperation)
+        );
+        assert_eq!(
+            Alignment::from_cigar("5MS", 0, 5, 10),
+            Err(CigarError::MissingLength('S'))
+        );
+        assert_eq!(
+            Alignment::from_cigar("5Q", 0, 5, 10),
+            Err(CigarError::InvalidOperation('Q'))
+        );
+        assert_eq!(
+            Alignment::from_cigar("2M1S2M", 0, 5, 10),
+            Err(CigarError::InnerClip('S'))
+        );
+        assert_eq!(
+            Alignment::from_cigar("2M1H1S", 0, 4, 10),
+            Err(CigarError::InnerClip('H'))
+        );
+        assert_eq!(
+            Alignment::from_cigar("1S1H2M", 0, 4, 10),
+            Err(CigarError::InnerClip('H'))
+        );
+        assert_eq!(
+            Alignment::from_cigar("5M", 0, 6, 10),
+            Err(CigarError::QueryLengthMismatch { cigar: 5, len: 6 })
+        );
+        assert_eq!(
+            Alignment::from_cigar("5M", 7, 5, 10),
+            Err(CigarError::ReferenceOutOfBounds { end: 12, len: 10 })
+        );
+    }
+}
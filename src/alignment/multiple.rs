@@ -0,0 +1,481 @@
+//! Multiple sequence alignments, with support for reading and writing the MAF,
+//! Clustal and aligned FASTA formats.
+
+use std::io::{self, BufRead, Write};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::gapped::{column_operation, is_gap};
+use super::{Alignment, AlignmentMode};
+use crate::strand::{ReqStrand, StrandError};
+
+// Number of columns per line in written Clustal and FASTA files.
+const LINE_WIDTH: usize = 60;
+
+/// A row of a multiple alignment, i.e. a gapped (part of a) sequence. As in MAF,
+/// `start` is the 0-based start of the aligned part on the given strand of the
+/// source sequence, i.e. it counts from the end of the source sequence for the
+/// reverse strand. `size` is the number of aligned bases and `src_len` the length
+/// of the source sequence.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct Row {
+    pub name: String,
+    pub start: usize,
+    pub size: usize,
+    pub strand: ReqStrand,
+    pub src_len: usize,
+    /// Aligned sequence, with gaps given as `-` (or `.`)
+    pub seq: Vec<u8>,
+}
+
+impl Row {
+    /// Create a row from its gapped sequence; the size is the number of non-gap
+    /// characters.
+    pub fn new(name: &str, start: usize, strand: ReqStrand, src_len: usize, seq: Vec<u8>) -> Self {
+        Row {
+            name: name.to_owned(),
+            start,
+            size: seq.iter().filter(|&&c| !is_gap(c)).count(),
+            strand,
+            src_len,
+            seq,
+        }
+    }
+
+    /// Create a row covering a whole forward strand sequence, as found in Clustal
+    /// and aligned FASTA files.
+    pub fn whole(name: &str, seq: Vec<u8>) -> Self {
+        let mut row = Row::new(name, 0, ReqStrand::Forward, 0, seq);
+        row.src_len = row.size;
+        row
+    }
+
+    /// The sequence without gaps.
+    pub fn ungapped(&self) -> Vec<u8> {
+        self.seq.iter().cloned().filter(|&c| !is_gap(c)).collect()
+    }
+}
+
+/// A multiple sequence alignment, e.g. a MAF block. All rows have the same number
+/// of columns.
+///
+/// # Example
+///
+/// ```
+/// use bio_types::alignment::multiple::MultipleAlignment;
+/// let maf = "\
+/// ##maf version=1
+/// a score=10.0
+/// s hg38.chr1 100 6 + 1000 AC-GTAT
+/// s mm10.chr2  50 7 - 2000 ACTGTTT
+/// ";
+/// let blocks = MultipleAlignment::read_maf(maf.as_bytes()).unwrap();
+/// assert_eq!(blocks[0].consensus(), b"ACTGTAT");
+/// let alignment = blocks[0].pairwise(0, 1);
+/// assert_eq!(alignment.cigar(false).unwrap(), "100S2=1D2=1X1=894S");
+///
+/// let mut out = Vec::new();
+/// MultipleAlignment::write_maf(&blocks, &mut out).unwrap();
+/// assert_eq!(MultipleAlignment::read_maf(&out[..]).unwrap(), blocks);
+/// ```
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct MultipleAlignment {
+    /// Score of the alignment, as given in MAF files
+    pub score: Option<f64>,
+    pub rows: Vec<Row>,
+}
+
+impl MultipleAlignment {
+    /// Create a multiple alignment from rows, checking that they have the same
+    /// number of columns.
+    pub fn new(rows: Vec<Row>) -> Result<Self, MultipleAlignmentError> {
+        if let Some(row) = rows.iter().find(|row| row.seq.len() != rows[0].seq.len()) {
+            return Err(MultipleAlignmentError::RowLengthMismatch(row.name.clone()));
+        }
+        Ok(MultipleAlignment { score: None, rows })
+    }
+
+    /// Number of columns.
+    pub fn ncols(&self) -> usize {
+        self.rows.first().map_or(0, |row| row.seq.len())
+    }
+
+    /// The characters of the given column, one per row.
+    pub fn column(&self, i: usize) -> Vec<u8> {
+        self.rows.iter().map(|row| row.seq[i]).collect()
+    }
+
+    /// Iterate over the columns.
+    pub fn columns(&self) -> impl Iterator<Item = Vec<u8>> + '_ {
+        (0..self.ncols()).map(move |i| self.column(i))
+    }
+
+    /// Gapped consensus sequence, with the most frequent base of each column (in
+    /// upper case, ties are broken by the order of the rows). A column becomes a gap
+    /// if it contains more gaps than occurrences of that base.
+    pub fn consensus(&self) -> Vec<u8> {
+        self.columns()
+            .map(|column| {
+                let mut counts = [0usize; 256];
+                let mut best = (0, b'-');
+                let mut gaps = 0;
+                for &c in column.iter() {
+                    if is_gap(c) {
+                        gaps += 1;
+                        continue;
+                    }
+                    let c = c.to_ascii_uppercase();
+                    counts[c as usize] += 1;
+                    if counts[c as usize] > best.0 {
+                        best = (counts[c as usize], c);
+                    }
+                }
+                if gaps > best.0 {
+                    b'-'
+                } else {
+                    best.1
+                }
+            })
+            .collect()
+    }
+
+    /// Project the multiple alignment onto a pairwise alignment of row `x` against
+    /// row `y`. Aligned bases are compared case-insensitively to distinguish
+    /// matches from substitutions, columns with gaps in both rows are skipped. The
+    /// alignment is in `Local` mode, positions refer to the strands of the rows and
+    /// the score is set to zero.
+    ///
+    /// # Panics
+    ///
+    /// If `x` or `y` is not a valid row index.
+    pub fn pairwise(&self, x: usize, y: usize) -> Alignment {
+        let (x, y) = (&self.rows[x], &self.rows[y]);
+        let operations = x
+            .seq
+            .iter()
+            .zip(y.seq.iter())
+            .filter_map(|(&a, &b)| column_operation(a, b))
+            .collect();
+        Alignment {
+            score: 0,
+            ystart: y.start,
+            xstart: x.start,
+            yend: y.start + y.size,
+            xend: x.start + x.size,
+            ylen: y.src_len,
+            xlen: x.src_len,
+            operations,
+            mode: AlignmentMode::Local,
+        }
+    }
+
+    /// Read all alignment blocks of a MAF file. Only `a` and `s` lines are
+    /// interpreted, other lines are skipped.
+    pub fn read_maf<R: BufRead>(reader: R) -> Result<Vec<Self>, MultipleAlignmentError> {
+        let mut blocks = Vec::new();
+        let mut block: Option<MultipleAlignment> = None;
+        for line in reader.lines() {
+            let line = line?;
+            let mut fields = line.split_whitespace();
+            match fields.next() {
+                Some("a") => {
+                    blocks.extend(block.take().map(Self::validated).transpose()?);
+                    let mut score = None;
+                    for field in fields {
+                        if let Some(value) = field.strip_prefix("score=") {
+                            score = Some(value.parse().map_err(|_| {
+                                MultipleAlignmentError::InvalidMafLine(line.clone())
+                            })?);
+                        }
+                    }
+                    block = Some(MultipleAlignment {
+                        score,
+                        rows: Vec::new(),
+                    });
+                }
+                Some("s") => {
+                    let block = block
+                        .as_mut()
+                        .ok_or_else(|| MultipleAlignmentError::InvalidMafLine(line.clone()))?;
+                    let fields: Vec<_> = fields.collect();
+                    if fields.len() != 6 {
+                        return Err(MultipleAlignmentError::InvalidMafLine(line.clone()));
+                    }
+                    let number = |s: &str| {
+                        s.parse::<usize>()
+                            .map_err(|_| MultipleAlignmentError::InvalidMafLine(line.clone()))
+                    };
+                    let row = Row {
+                        name: fields[0].to_owned(),
+                        start: number(fields[1])?,
+                        size: number(fields[2])?,
+                        strand: fields[3].parse()?,
+                        src_len: number(fields[4])?,
+                        seq: fields[5].as_bytes().to_vec(),
+                    };
+                    if row.size != row.ungapped().len() {
+                        return Err(MultipleAlignmentError::SizeMismatch(row.name));
+                    }
+                    block.rows.push(row);
+                }
+                None => blocks.extend(block.take().map(Self::validated).transpose()?),
+                _ => {}
+            }
+        }
+        blocks.extend(block.map(Self::validated).transpose()?);
+        Ok(blocks)
+    }
+
+    /// Write alignment blocks as a MAF file, including the header.
+    pub fn write_maf<W: Write>(blocks: &[Self], mut writer: W) -> io::Result<()> {
+        writeln!(writer, "##maf version=1")?;
+        for block in blocks {
+            writeln!(writer)?;
+            match block.score {
+                Some(score) => writeln!(writer, "a score={:?}", score)?,
+                None => writeln!(writer, "a")?,
+            }
+            let width = |f: fn(&Row) -> String| {
+                block.rows.iter().map(|row| f(row).len()).max().unwrap_or(0)
+            };
+            let name_width = width(|row| row.name.clone());
+            let start_width = width(|row| row.start.to_string());
+            let size_width = width(|row| row.size.to_string());
+            let len_width = width(|row| row.src_len.to_string());
+            for row in block.rows.iter() {
+                writeln!(
+                    writer,
+                    "s {:<name_width$} {:>start_width$} {:>size_width$} {} {:>len_width$} {}",
+                    row.name,
+                    row.start,
+                    row.size,
+                    row.strand,
+                    row.src_len,
+                    String::from_utf8_lossy(&row.seq),
+                    name_width = name_width,
+                    start_width = start_width,
+                    size_width = size_width,
+                    len_width = len_width,
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Read a Clustal file. Rows cover whole forward strand sequences.
+    pub fn read_clustal<R: BufRead>(reader: R) -> Result<Self, MultipleAlignmentError> {
+        let mut rows: Vec<Row> = Vec::new();
+        let mut lines = reader.lines();
+        match lines.next().transpose()? {
+            Some(header) if header.starts_with("CLUSTAL") => {}
+            _ => return Err(MultipleAlignmentError::MissingClustalHeader),
+        }
+        for line in lines {
+            let line = line?;
+            // skip empty lines and conservation lines
+            if line.trim().is_empty() || line.starts_with(char::is_whitespace) {
+                continue;
+            }
+            let mut fields = line.split_whitespace();
+            let (name, seq) = match (fields.next(), fields.next()) {
+                (Some(name), Some(seq)) => (name, seq.as_bytes()),
+                _ => return Err(MultipleAlignmentError::InvalidClustalLine(line.clone())),
+            };
+            match rows.iter_mut().find(|row| row.name == name) {
+                Some(row) => row.seq.extend_from_slice(seq),
+                None => rows.push(Row::whole(name, seq.to_vec())),
+            }
+        }
+        Self::new(
+            rows.into_iter()
+                .map(|row| Row::whole(&row.name, row.seq))
+                .collect(),
+        )
+    }
+
+    /// Write the alignment as a Clustal file, with conserved columns marked by `*`.
+    pub fn write_clustal<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writeln!(writer, "CLUSTAL W multiple sequence alignment")?;
+        let name_width = self
+            .rows
+            .iter()
+            .map(|row| row.name.len())
+            .max()
+            .unwrap_or(0);
+        let conserved: Vec<u8> = self
+            .columns()
+            .map(|column| {
+                let first = column[0].to_ascii_uppercase();
+                if !is_gap(first) && column.iter().all(|c| c.to_ascii_uppercase() == first) {
+                    b'*'
+                } else {
+                    b' '
+                }
+            })
+            .collect();
+        for offset in (0..self.ncols()).step_by(LINE_WIDTH) {
+            let end = (offset + LINE_WIDTH).min(self.ncols());
+            writeln!(writer)?;
+            for row in self.rows.iter() {
+                writeln!(
+                    writer,
+                    "{:<width$}    {}",
+                    row.name,
+                    String::from_utf8_lossy(&row.seq[offset..end]),
+                    width = name_width
+                )?;
+            }
+            writeln!(
+                writer,
+                "{:<width$}    {}",
+                "",
+                String::from_utf8_lossy(&conserved[offset..end]),
+                width = name_width
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Read an aligned FASTA file. The first word of each header is used as the
+    /// name, rows cover whole forward strand sequences.
+    pub fn read_fasta<R: BufRead>(reader: R) -> Result<Self, MultipleAlignmentError> {
+        let mut rows: Vec<Row> = Vec::new();
+        for line in reader.lines() {
+            let line = line?;
+            if let Some(header) = line.strip_prefix('>') {
+                let name = header.split_whitespace().next().unwrap_or("");
+                rows.push(Row::whole(name, Vec::new()));
+            } else if !line.trim().is_empty() {
+                rows.last_mut()
+                    .ok_or(MultipleAlignmentError::MissingFastaHeader)?
+                    .seq
+                    .extend_from_slice(line.trim().as_bytes());
+            }
+        }
+        Self::new(
+            rows.into_iter()
+                .map(|row| Row::whole(&row.name, row.seq))
+                .collect(),
+        )
+    }
+
+    /// Write the alignment as an aligned FASTA file.
+    pub fn write_fasta<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        for row in self.rows.iter() {
+            writeln!(writer, ">{}", row.name)?;
+            for line in row.seq.chunks(LINE_WIDTH) {
+                writer.write_all(line)?;
+                writeln!(writer)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn validated(self) -> Result<Self, MultipleAlignmentError> {
+        let score = self.score;
+        let mut alignment = Self::new(self.rows)?;
+        alignment.score = score;
+        Ok(alignment)
+    }
+}
+
+/// Errors that arise when constructing, reading or writing multiple alignments.
+#[derive(Error, Debug)]
+pub enum MultipleAlignmentError {
+    #[error("row {0:?} has a different number of columns than the first row")]
+    RowLengthMismatch(String),
+    #[error("size of row {0:?} does not match its number of bases")]
+    SizeMismatch(String),
+    #[error("invalid MAF line: {0:?}")]
+    InvalidMafLine(String),
+    #[error("error parsing strand of MAF line")]
+    ParseStrand(#[from] StrandError),
+    #[error("Clustal file does not start with a CLUSTAL header")]
+    MissingClustalHeader,
+    #[error("invalid Clustal line: {0:?}")]
+    InvalidClustalLine(String),
+    #[error("FASTA file does not start with a header")]
+    MissingFastaHeader,
+    #[error("error reading alignment")]
+    Io(#[from] io::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alignment::AlignmentOperation::*;
+
+    fn alignment() -> MultipleAlignment {
+        MultipleAlignment::new(vec![
+            Row::whole("a", b"ACG-TA".to_vec()),
+            Row::whole("b", b"ACGGT-".to_vec()),
+            Row::whole("c", b"TCG-CA".to_vec()),
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn test_multiple_alignment() {
+        let msa = alignment();
+        assert_eq!(msa.ncols(), 6);
+        assert_eq!(msa.column(3), b"-G-");
+        assert_eq!(msa.columns().count(), 6);
+        assert_eq!(msa.consensus(), b"ACG-TA");
+        assert_eq!(msa.rows[1].size, 5);
+
+        let pairwise = msa.pairwise(0, 1);
+        assert_eq!(pairwise.operations, [Match, Match, Match, Del, Match, Ins]);
+        assert_eq!((pairwise.xend, pairwise.yend), (5, 5));
+        let pairwise = msa.pairwise(0, 2);
+        assert_eq!(pairwise.operations, [Subst, Match, Match, Subst, Match]);
+
+        assert!(matches!(
+            MultipleAlignment::new(vec![
+                Row::whole("a", b"AC".to_vec()),
+                Row::whole("b", b"A".to_vec())
+            ]),
+            Err(MultipleAlignmentError::RowLengthMismatch(name)) if name == "b"
+        ));
+    }
+
+    #[test]
+    fn test_io() {
+        let msa = alignment();
+
+        let mut clustal = Vec::new();
+        msa.write_clustal(&mut clustal).unwrap();
+        assert_eq!(
+            String::from_utf8_lossy(&clustal),
+            "CLUSTAL W multiple sequence alignment\n\na    ACG-TA\nb    ACGGT-\nc    TCG-CA\n      **   \n"
+        );
+        assert_eq!(MultipleAlignment::read_clustal(&clustal[..]).unwrap(), msa);
+
+        let mut fasta = Vec::new();
+        msa.write_fasta(&mut fasta).unwrap();
+        assert_eq!(
+            String::from_utf8_lossy(&fasta),
+            ">a\nACG-TA\n>b\nACGGT-\n>c\nTCG-CA\n"
+        );
+        assert_eq!(MultipleAlignment::read_fasta(&fasta[..]).unwrap(), msa);
+
+        let maf = "a\ns x 0 6 + 6 AC-GTA\n";
+        assert!(matches!(
+            MultipleAlignment::read_maf(maf.as_bytes()),
+            Err(MultipleAlignmentError::SizeMismatch(name)) if name == "x"
+        ));
+        assert!(matches!(
+            MultipleAlignment::read_maf("s x 0 5 + 5 ACGTA\n".as_bytes()),
+            Err(MultipleAlignmentError::InvalidMafLine(_))
+        ));
+        let maf = "a score=1\ns x 0 3 + 5 ACG\ns y 2 3 - 5 ACG\n\na\ns x 3 2 + 5 TA\n";
+        let blocks = MultipleAlignment::read_maf(maf.as_bytes()).unwrap();
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[0].score, Some(1.0));
+        assert_eq!(blocks[0].rows[1].strand, ReqStrand::Reverse);
+        assert_eq!(blocks[1].rows[0].start, 3);
+    }
+}
@@ -0,0 +1,360 @@
+//! Records of the PAF format, the pairwise mapping format emitted by minimap2 and
+//! other long-read mappers.
+
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::cigar::run_len;
+use super::{Alignment, AlignmentOperation, Cigar, CigarError, CigarOp};
+use crate::strand::{ReqStrand, StrandError};
+
+/// An optional SAM-like tag of a PAF record, e.g. `tp:A:P`.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(new, Debug, PartialEq, Eq, Clone, Hash)]
+pub struct PafTag {
+    /// Two-character name of the tag
+    pub name: String,
+    /// Type of the value, e.g. `A`, `i`, `f` or `Z`
+    pub kind: char,
+    /// Value of the tag
+    pub value: String,
+}
+
+impl Display for PafTag {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}:{}:{}", self.name, self.kind, self.value)
+    }
+}
+
+impl FromStr for PafTag {
+    type Err = PafError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut fields = s.splitn(3, ':');
+        match (fields.next(), fields.next(), fields.next()) {
+            (Some(name), Some(kind), Some(value)) if kind.chars().count() == 1 => Ok(PafTag {
+                name: name.to_owned(),
+                kind: kind.chars().next().unwrap(),
+                value: value.to_owned(),
+            }),
+            _ => Err(PafError::InvalidTag(s.to_owned())),
+        }
+    }
+}
+
+/// A single line of a PAF file. Coordinates are 0-based and half-open. As in the
+/// PAF format, query coordinates always refer to the forward strand of the query,
+/// also if it maps to the reverse strand of the target.
+///
+/// # Example
+///
+/// ```
+/// use bio_types::alignment::paf::PafRecord;
+/// use bio_types::strand::ReqStrand;
+/// let line = "read1\t10\t2\t9\t-\tchr1\t20\t5\t11\t6\t7\t60\ttp:A:P\tcg:Z:3M1I3M";
+/// let record: PafRecord = line.parse().unwrap();
+/// assert_eq!(record.strand, ReqStrand::Reverse);
+/// assert_eq!(record.tag("tp").unwrap().value, "P");
+/// assert_eq!(record.to_string(), line);
+///
+/// let alignment = record.to_alignment().unwrap();
+/// assert_eq!((alignment.xstart, alignment.xend), (1, 8));
+/// assert_eq!((alignment.ystart, alignment.yend), (5, 11));
+/// assert_eq!(alignment.cigar(false).unwrap(), "1S3=1I3=2S");
+/// ```
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct PafRecord {
+    /// Name of the query sequence
+    pub query_name: String,
+    /// Length of the query sequence
+    pub query_len: usize,
+    /// Start of the alignment on the forward strand of the query
+    pub query_start: usize,
+    /// End of the alignment on the forward strand of the query
+    pub query_end: usize,
+    /// Relative strand of query and target
+    pub strand: ReqStrand,
+    /// Name of the target sequence
+    pub target_name: String,
+    /// Length of the target sequence
+    pub target_len: usize,
+    /// Start of the alignment on the target
+    pub target_start: usize,
+    /// End of the alignment on the target
+    pub target_end: usize,
+    /// Number of matching bases in the alignment
+    pub matches: usize,
+    /// Number of bases, including gaps but excluding reference skips, in the alignment
+    pub block_len: usize,
+    /// Mapping quality (255 if missing)
+    pub mapq: u8,
+    /// Optional tags
+    pub tags: Vec<PafTag>,
+}
+
+impl PafRecord {
+    /// Create a record from an alignment of query x against target y. If the query
+    /// maps to the reverse strand, x is expected to be the reverse complement of the
+    /// query, and the query coordinates are flipped accordingly. The score of the
+    /// alignment is stored in the `AS:i` tag and its CIGAR in the `cg:Z` tag.
+    ///
+    /// The number of matches counts the `Match` operations of the alignment. For
+    /// alignments built from CIGARs with `M` operations, which do not distinguish
+    /// matches from mismatches, it therefore overstates the number of matching
+    /// residues.
+    ///
+    /// An error is returned if no CIGAR can be calculated from the alignment.
+    pub fn from_alignment(
+        alignment: &Alignment,
+        query_name: &str,
+        target_name: &str,
+        strand: ReqStrand,
+        mapq: u8,
+    ) -> Result<Self, PafError> {
+        let mut cigar = alignment.to_cigar(false)?;
+        cigar.0.retain(|op| !op.is_clip());
+
+        let (query_start, query_end) = match strand {
+            ReqStrand::Forward => (alignment.xstart, alignment.xend),
+            ReqStrand::Reverse => (
+                alignment.xlen - alignment.xend,
+                alignment.xlen - alignment.xstart,
+            ),
+        };
+        let matches = alignment
+            .operations
+            .iter()
+            .filter(|op| **op == AlignmentOperation::Match)
+            .count();
+
+        Ok(PafRecord {
+            query_name: query_name.to_owned(),
+            query_len: alignment.xlen,
+            query_start,
+            query_end,
+            strand,
+            target_name: target_name.to_owned(),
+            target_len: alignment.ylen,
+            target_start: alignment.ystart,
+            target_end: alignment.yend,
+            matches,
+            block_len: cigar
+                .iter()
+                .filter(|op| !matches!(op, CigarOp::RefSkip(_)))
+                .map(|op| op.len() as usize)
+                .sum(),
+            mapq,
+            tags: vec![
+                PafTag::new("AS".to_owned(), 'i', alignment.score.to_string()),
+                PafTag::new("cg".to_owned(), 'Z', cigar.to_string()),
+            ],
+        })
+    }
+
+    /// Return the tag with the given name, if present.
+    pub fn tag(&self, name: &str) -> Option<&PafTag> {
+        self.tags.iter().find(|tag| tag.name == name)
+    }
+
+    /// Parse the CIGAR stored in the `cg:Z` tag, if present.
+    pub fn cigar(&self) -> Option<Result<Cigar, CigarError>> {
+        self.tag("cg").map(|tag| tag.value.parse())
+    }
+
+    /// Convert the record into a `Custom` mode alignment of the query (x) against the
+    /// target (y), based on the CIGAR in the `cg:Z` tag. For reverse strand
+    /// records, x is the reverse complement of the query, i.e. the query
+    /// coordinates are flipped. The score is taken from the `AS:i` tag if present,
+    /// and zero otherwise.
+    pub fn to_alignment(&self) -> Result<Alignment, PafError> {
+        let cg = self.cigar().ok_or(PafError::MissingCigar)??;
+        if self.query_start > self.query_end || self.query_end > self.query_len {
+            return Err(PafError::InvalidQueryRange);
+        }
+        let (xstart, xend) = match self.strand {
+            ReqStrand::Forward => (self.query_start, self.query_end),
+            ReqStrand::Reverse => (
+                self.query_len - self.query_end,
+                self.query_len - self.query_start,
+            ),
+        };
+
+        let mut cigar = Cigar::new();
+        cigar.push(CigarOp::SoftClip(run_len(xstart)?));
+        cigar.0.extend(cg.0);
+        cigar.push(CigarOp::SoftClip(run_len(self.query_len - xend)?));
+
+        let mut alignment =
+            cigar.to_alignment(self.target_start, self.query_len, self.target_len)?;
+        if alignment.yend != self.target_end {
+            return Err(PafError::InconsistentCigar);
+        }
+        if let Some(tag) = self.tag("AS") {
+            alignment.score = tag
+                .value
+                .parse()
+                .map_err(|_| PafError::InvalidTag(tag.to_string()))?;
+        }
+        Ok(alignment)
+    }
+}
+
+impl Display for PafRecord {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+            self.query_name,
+            self.query_len,
+            self.query_start,
+            self.query_end,
+            self.strand,
+            self.target_name,
+            self.target_len,
+            self.target_start,
+            self.target_end,
+            self.matches,
+            self.block_len,
+            self.mapq
+        )?;
+        for tag in self.tags.iter() {
+            write!(f, "\t{}", tag)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for PafRecord {
+    type Err = PafError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fields: Vec<&str> = s.trim_end_matches(['\r', '\n']).split('\t').collect();
+        if fields.len() < 12 {
+            return Err(PafError::MissingFields(fields.len()));
+        }
+
+        Ok(PafRecord {
+            query_name: fields[0].to_owned(),
+            query_len: fields[1].parse()?,
+            query_start: fields[2].parse()?,
+            query_end: fields[3].parse()?,
+            strand: fields[4].parse()?,
+            target_name: fields[5].to_owned(),
+            target_len: fields[6].parse()?,
+            target_start: fields[7].parse()?,
+            target_end: fields[8].parse()?,
+            matches: fields[9].parse()?,
+            block_len: fields[10].parse()?,
+            mapq: fields[11].parse()?,
+            tags: fields[12..]
+                .iter()
+                .map(|tag| tag.parse())
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+/// Errors that arise when parsing or converting PAF records.
+#[derive(Error, Debug)]
+pub enum PafError {
+    #[error("PAF record has only {0} of the 12 mandatory fields")]
+    MissingFields(usize),
+    #[error("error parsing integer field of PAF record")]
+    ParseInt(#[from] std::num::ParseIntError),
+    #[error("error parsing strand of PAF record")]
+    ParseStrand(#[from] StrandError),
+    #[error("invalid PAF tag: {0:?}")]
+    InvalidTag(String),
+    #[error("PAF record has no cg:Z tag")]
+    MissingCigar,
+    #[error("query coordinates of PAF record exceed the query length")]
+    InvalidQueryRange,
+    #[error("CIGAR of PAF record does not match the target coordinates")]
+    InconsistentCigar,
+    #[error("invalid CIGAR")]
+    Cigar(#[from] CigarError),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alignment::AlignmentMode;
+    use crate::alignment::AlignmentOperation::*;
+
+    #[test]
+    fn test_parse() {
+        let record: PafRecord =
+            "q\t100\t10\t90\t+\tt\t1000\t500\t579\t75\t81\t255\tAS:i:140\tcg:Z:40M1I39M\n"
+                .parse()
+                .unwrap();
+        assert_eq!(record.query_len, 100);
+        assert_eq!(record.strand, ReqStrand::Forward);
+        assert_eq!(record.mapq, 255);
+        assert_eq!(record.tags.len(), 2);
+        assert_eq!(record.cigar().unwrap().unwrap().to_string(), "40M1I39M");
+
+        let alignment = record.to_alignment().unwrap();
+        assert_eq!(alignment.score, 140);
+        assert_eq!((alignment.xstart, alignment.xend), (10, 90));
+        assert_eq!((alignment.ystart, alignment.yend), (500, 579));
+
+        assert!(matches!(
+            "q\t100\t10\t90\t+\tt\t1000\t500\t579\t75\t81".parse::<PafRecord>(),
+            Err(PafError::MissingFields(11))
+        ));
+        assert!(matches!(
+            "q\t100\t10\t90\t*\tt\t1000\t500\t579\t75\t81\t60".parse::<PafRecord>(),
+            Err(PafError::ParseStrand(_))
+        ));
+        assert!(matches!(
+            "q\t100\t10\t90\t+\tt\t1000\t500\t579\t75\t81\t60\tcg".parse::<PafRecord>(),
+            Err(PafError::InvalidTag(_))
+        ));
+        let record: PafRecord = "q\t100\t10\t90\t+\tt\t1000\t500\t579\t75\t81\t60\tcg:Z:80M"
+            .parse()
+            .unwrap();
+        assert!(matches!(
+            record.to_alignment(),
+            Err(PafError::InconsistentCigar)
+        ));
+        let record: PafRecord = "q\t100\t10\t190\t-\tt\t1000\t500\t580\t75\t81\t60\tcg:Z:80M"
+            .parse()
+            .unwrap();
+        assert!(matches!(
+            record.to_alignment(),
+            Err(PafError::InvalidQueryRange)
+        ));
+    }
+
+    #[test]
+    fn test_from_alignment() {
+        let alignment = Alignment {
+            score: 3,
+            xstart: 1,
+            ystart: 5,
+            xend: 8,
+            yend: 11,
+            ylen: 20,
+            xlen: 10,
+            operations: vec![Match, Subst, Match, Ins, Match, Match, Match],
+            mode: AlignmentMode::Local,
+        };
+        let record =
+            PafRecord::from_alignment(&alignment, "read1", "chr1", ReqStrand::Reverse, 60).unwrap();
+        assert_eq!(
+            record.to_string(),
+            "read1\t10\t2\t9\t-\tchr1\t20\t5\t11\t5\t7\t60\tAS:i:3\tcg:Z:1=1X1=1I3="
+        );
+
+        let back = record.to_alignment().unwrap();
+        assert_eq!((back.xstart, back.xend), (alignment.xstart, alignment.xend));
+        assert_eq!((back.ystart, back.yend), (alignment.ystart, alignment.yend));
+        assert_eq!(back.score, alignment.score);
+        assert_eq!(back.cigar(false), alignment.cigar(false));
+    }
+}
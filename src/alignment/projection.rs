@@ -0,0 +1,216 @@
+//! Projection of positions between the two sequences of an alignment, e.g. to lift
+//! read-level features onto the reference.
+
+use super::{Alignment, AlignmentOperation};
+
+/// The result of projecting a position of one sequence of an alignment onto the
+/// other sequence.
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Hash)]
+pub enum Projection {
+    /// The position is aligned to the given position of the other sequence.
+    Aligned(usize),
+    /// The position is inside a gap, i.e. an insertion or deletion. The gap lies
+    /// between the given positions of the other sequence, unless it is at one of
+    /// its ends.
+    Gap {
+        left: Option<usize>,
+        right: Option<usize>,
+    },
+    /// The position is not part of the alignment, e.g. because it is clipped.
+    Unaligned,
+}
+
+impl Projection {
+    /// Aligned position, or `None` for gaps and unaligned positions.
+    pub fn aligned(&self) -> Option<usize> {
+        match *self {
+            Projection::Aligned(pos) => Some(pos),
+            _ => None,
+        }
+    }
+
+    /// Aligned position, or the nearest position to the left of a gap.
+    pub fn nearest_left(&self) -> Option<usize> {
+        match *self {
+            Projection::Aligned(pos) => Some(pos),
+            Projection::Gap { left, .. } => left,
+            Projection::Unaligned => None,
+        }
+    }
+
+    /// Aligned position, or the nearest position to the right of a gap.
+    pub fn nearest_right(&self) -> Option<usize> {
+        match *self {
+            Projection::Aligned(pos) => Some(pos),
+            Projection::Gap { right, .. } => right,
+            Projection::Unaligned => None,
+        }
+    }
+}
+
+impl Alignment {
+    /// Project a position of the query sequence x onto the reference sequence y.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bio_types::alignment::{Alignment,AlignmentMode,Projection};
+    /// use bio_types::alignment::AlignmentOperation::*;
+    /// let alignment = Alignment {
+    ///     score: 5,
+    ///     xstart: 3,
+    ///     ystart: 0,
+    ///     xend: 9,
+    ///     yend: 10,
+    ///     ylen: 10,
+    ///     xlen: 10,
+    ///     operations: vec![Match, Match, Match, Subst, Ins, Ins, Del, Del],
+    ///     mode: AlignmentMode::Semiglobal,
+    /// };
+    /// assert_eq!(alignment.x_to_y(0), Projection::Unaligned);
+    /// assert_eq!(alignment.x_to_y(3), Projection::Aligned(0));
+    /// assert_eq!(alignment.x_to_y(7), Projection::Gap { left: Some(3), right: Some(4) });
+    /// assert_eq!(alignment.x_to_y(7).nearest_right(), Some(4));
+    /// assert_eq!(alignment.y_to_x(5), Projection::Gap { left: Some(8), right: Some(9) });
+    /// ```
+    pub fn x_to_y(&self, pos: usize) -> Projection {
+        self.project(&[pos], true)[0]
+    }
+
+    /// Project a position of the reference sequence y onto the query sequence x.
+    pub fn y_to_x(&self, pos: usize) -> Projection {
+        self.project(&[pos], false)[0]
+    }
+
+    /// Project positions of the query sequence x onto the reference sequence y.
+    /// The positions have to be sorted in ascending order. The running time is
+    /// linear in the number of positions and alignment operations.
+    pub fn x_to_y_sorted(&self, positions: &[usize]) -> Vec<Projection> {
+        self.project(positions, true)
+    }
+
+    /// Project positions of the reference sequence y onto the query sequence x.
+    /// The positions have to be sorted in ascending order. The running time is
+    /// linear in the number of positions and alignment operations.
+    pub fn y_to_x_sorted(&self, positions: &[usize]) -> Vec<Projection> {
+        self.project(positions, false)
+    }
+
+    fn project(&self, positions: &[usize], from_x: bool) -> Vec<Projection> {
+        debug_assert!(
+            positions.windows(2).all(|w| w[0] <= w[1]),
+            "positions have to be sorted"
+        );
+        let to_len = if from_x { self.ylen } else { self.xlen };
+        let mut projections = Vec::with_capacity(positions.len());
+        let mut positions = positions.iter().peekable();
+
+        for (x_i, y_i, op) in self.positioned_operations() {
+            let (from, to) = if from_x { (x_i, y_i) } else { (y_i, x_i) };
+            // number of consumed bases in both sequences, and whether the op aligns them
+            let (from_len, aligned) = match (op, from_x) {
+                (AlignmentOperation::Match | AlignmentOperation::Subst, _) => (1, true),
+                (AlignmentOperation::Ins, true)
+                | (AlignmentOperation::Del | AlignmentOperation::RefSkip, false) => (1, false),
+                (AlignmentOperation::Xclip(len), true)
+                | (AlignmentOperation::Yclip(len), false) => (len, false),
+                _ => (0, false),
+            };
+            while let Some(&&pos) = positions.peek() {
+                if pos < from {
+                    projections.push(Projection::Unaligned);
+                } else if pos < from + from_len {
+                    projections.push(match op {
+                        _ if aligned => Projection::Aligned(to),
+                        AlignmentOperation::Ins
+                        | AlignmentOperation::Del
+                        | AlignmentOperation::RefSkip => Projection::Gap {
+                            left: to.checked_sub(1),
+                            right: if to < to_len { Some(to) } else { None },
+                        },
+                        _ => Projection::Unaligned,
+                    });
+                } else {
+                    break;
+                }
+                positions.next();
+            }
+        }
+        projections.resize(projections.len() + positions.count(), Projection::Unaligned);
+        projections
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alignment::AlignmentMode;
+    use crate::alignment::AlignmentOperation::*;
+
+    #[test]
+    fn test_projection() {
+        let alignment = Alignment {
+            score: 5,
+            xstart: 0,
+            ystart: 5,
+            xend: 4,
+            yend: 10,
+            ylen: 10,
+            xlen: 5,
+            operations: vec![Yclip(5), Match, Subst, Subst, Ins, Del, Del, Xclip(1)],
+            mode: AlignmentMode::Custom,
+        };
+        assert_eq!(
+            alignment.x_to_y_sorted(&[0, 1, 2, 3, 4, 5]),
+            [
+                Projection::Aligned(5),
+                Projection::Aligned(6),
+                Projection::Aligned(7),
+                Projection::Gap {
+                    left: Some(7),
+                    right: Some(8)
+                },
+                Projection::Unaligned,
+                Projection::Unaligned
+            ]
+        );
+        assert_eq!(
+            alignment.y_to_x_sorted(&[4, 5, 7, 8, 9]),
+            [
+                Projection::Unaligned,
+                Projection::Aligned(0),
+                Projection::Aligned(2),
+                Projection::Gap {
+                    left: Some(3),
+                    right: Some(4)
+                },
+                Projection::Gap {
+                    left: Some(3),
+                    right: Some(4)
+                },
+            ]
+        );
+
+        let alignment = Alignment {
+            score: 1,
+            xstart: 0,
+            ystart: 0,
+            xend: 2,
+            yend: 3,
+            ylen: 3,
+            xlen: 2,
+            operations: vec![Del, Match, Match],
+            mode: AlignmentMode::Global,
+        };
+        assert_eq!(
+            alignment.y_to_x(0),
+            Projection::Gap {
+                left: None,
+                right: Some(0)
+            }
+        );
+        assert_eq!(alignment.y_to_x(0).nearest_left(), None);
+        assert_eq!(alignment.y_to_x(2), Projection::Aligned(1));
+        assert_eq!(alignment.x_to_y(1).aligned(), Some(2));
+    }
+}
@@ -0,0 +1,514 @@
+//! Rendering of alignments for humans. The layout of the alignment columns is
+//! shared by all output styles, which are implemented as a [`Renderer`].
+
+use std::fmt::Write;
+
+use super::{Alignment, AlignmentMode, AlignmentOperation, TextSlice};
+use crate::sequence::SequenceRead;
+
+/// The kind of an alignment column.
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Hash)]
+pub enum ColumnKind {
+    Match,
+    Subst,
+    Ins,
+    Del,
+    Skip,
+    Clip,
+}
+
+/// A base of x or y shown in an alignment column, together with its position in
+/// the sequence.
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Hash)]
+pub struct Cell {
+    pub pos: usize,
+    pub base: u8,
+}
+
+/// A column of a rendered alignment. For gaps and clipped bases, the cell of the
+/// other sequence is `None`.
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Hash)]
+pub struct Column {
+    pub kind: ColumnKind,
+    pub x: Option<Cell>,
+    pub y: Option<Cell>,
+}
+
+impl Column {
+    /// Character shown between the bases of x and y: '|' for a match, '\\' for a
+    /// mismatch, '+' for an insertion, 'x' for a deletion, '.' for a reference skip
+    /// and ' ' for clipping.
+    pub fn symbol(&self) -> char {
+        match self.kind {
+            ColumnKind::Match => '|',
+            ColumnKind::Subst => '\\',
+            ColumnKind::Ins => '+',
+            ColumnKind::Del => 'x',
+            ColumnKind::Skip => '.',
+            ColumnKind::Clip => ' ',
+        }
+    }
+
+    /// Character shown for the given cell of this column: the base, '-' for a gap
+    /// and ' ' for the other sequence of a clipped column.
+    pub fn char(&self, cell: Option<Cell>) -> char {
+        match (cell, self.kind) {
+            (Some(cell), _) => cell.base as char,
+            (None, ColumnKind::Clip) => ' ',
+            (None, _) => '-',
+        }
+    }
+}
+
+/// An output style for alignments. The alignment columns are wrapped into blocks,
+/// each of which is rendered separately.
+pub trait Renderer {
+    /// Render a block of alignment columns.
+    fn block(&mut self, columns: &[Column], out: &mut String);
+
+    /// Write anything that precedes the first block of the given alignment.
+    fn begin(&mut self, _alignment: &Alignment, _out: &mut String) {}
+
+    /// Write anything that follows the last block.
+    fn end(&mut self, _out: &mut String) {}
+}
+
+/// The plain text format of [`Alignment::pretty`](../struct.Alignment.html#method.pretty).
+#[derive(Debug, Default, Copy, Clone)]
+pub struct PlainRenderer;
+
+impl Renderer for PlainRenderer {
+    fn block(&mut self, columns: &[Column], out: &mut String) {
+        for row in [Row::X, Row::Symbols, Row::Y] {
+            out.extend(columns.iter().map(|col| row.char(col)));
+            out.push('\n');
+        }
+        out.push_str("\n\n");
+    }
+}
+
+// Base qualities are shaded on a scale up to this value.
+const MAX_SHADED_QUAL: u8 = 40;
+
+// The three rows of a rendered alignment block.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+enum Row {
+    X,
+    Symbols,
+    Y,
+}
+
+impl Row {
+    fn cell(self, col: &Column) -> Option<Cell> {
+        match self {
+            Row::X => col.x,
+            Row::Symbols => None,
+            Row::Y => col.y,
+        }
+    }
+
+    fn char(self, col: &Column) -> char {
+        match self {
+            Row::Symbols => col.symbol(),
+            _ => col.char(self.cell(col)),
+        }
+    }
+
+    // Quality of the base of x shown in the column, if any.
+    fn qual(self, col: &Column, read: Option<&dyn SequenceRead>) -> Option<u8> {
+        match (self, read, col.x) {
+            (Row::X, Some(read), Some(cell)) => Some(read.base_qual(cell.pos).min(MAX_SHADED_QUAL)),
+            _ => None,
+        }
+    }
+}
+
+/// Colored output for terminals, using ANSI escape codes. Substitutions are
+/// highlighted in red, gaps in yellow, and clipped bases are dimmed. If a read is
+/// given, the bases of x are shaded by their quality, from dark gray (quality
+/// zero) to white (quality 40 and above). x has to be the sequence of the read.
+#[derive(Default, Copy, Clone)]
+pub struct AnsiRenderer<'a> {
+    pub read: Option<&'a dyn SequenceRead>,
+}
+
+impl<'a> AnsiRenderer<'a> {
+    pub fn new() -> Self {
+        AnsiRenderer { read: None }
+    }
+
+    pub fn with_read(read: &'a dyn SequenceRead) -> Self {
+        AnsiRenderer { read: Some(read) }
+    }
+
+    fn row(&self, columns: &[Column], row: Row, out: &mut String) {
+        for col in columns {
+            out.push_str(match col.kind {
+                ColumnKind::Subst => "\x1b[41m",
+                ColumnKind::Ins | ColumnKind::Del => "\x1b[43m",
+                ColumnKind::Skip | ColumnKind::Clip => "\x1b[2m",
+                ColumnKind::Match => "",
+            });
+            if let Some(qual) = row.qual(col, self.read) {
+                let gray = 236 + qual as usize * 19 / MAX_SHADED_QUAL as usize;
+                write!(out, "\x1b[38;5;{}m", gray).unwrap();
+            }
+            out.push(row.char(col));
+            out.push_str("\x1b[0m");
+        }
+        out.push('\n');
+    }
+}
+
+impl Renderer for AnsiRenderer<'_> {
+    fn block(&mut self, columns: &[Column], out: &mut String) {
+        for row in [Row::X, Row::Symbols, Row::Y] {
+            self.row(columns, row, out);
+        }
+        out.push('\n');
+    }
+}
+
+/// HTML output, with the 1-based positions of the first and last base of each row
+/// in the margins. Substitutions and gaps are highlighted. If a read is given, the
+/// bases of x are shaded by their quality through their opacity. x has to be the
+/// sequence of the read.
+#[derive(Default, Copy, Clone)]
+pub struct HtmlRenderer<'a> {
+    pub read: Option<&'a dyn SequenceRead>,
+    // width of the positions in the margins
+    width: usize,
+}
+
+impl<'a> HtmlRenderer<'a> {
+    pub fn new() -> Self {
+        HtmlRenderer {
+            read: None,
+            width: 0,
+        }
+    }
+
+    pub fn with_read(read: &'a dyn SequenceRead) -> Self {
+        HtmlRenderer {
+            read: Some(read),
+            width: 0,
+        }
+    }
+
+    fn margin(&self, pos: Option<usize>, out: &mut String) {
+        match pos {
+            Some(pos) => write!(out, " {:>width$} ", pos + 1, width = self.width).unwrap(),
+            None => out.push_str(&" ".repeat(self.width + 2)),
+        }
+    }
+
+    fn row(&self, columns: &[Column], row: Row, out: &mut String) {
+        let mut cells = columns.iter().filter_map(|col| row.cell(col));
+        let first = cells.next();
+        let last = cells.next_back().or(first);
+        self.margin(first.map(|cell| cell.pos), out);
+        for col in columns {
+            let background = match col.kind {
+                ColumnKind::Subst => Some("#f99"),
+                ColumnKind::Ins | ColumnKind::Del => Some("#fd6"),
+                _ => None,
+            };
+            let opacity = row
+                .qual(col, self.read)
+                .map(|qual| 0.3 + 0.7 * qual as f64 / MAX_SHADED_QUAL as f64);
+            let c = match row.char(col) {
+                '<' => "&lt;".to_owned(),
+                '>' => "&gt;".to_owned(),
+                '&' => "&amp;".to_owned(),
+                c => c.to_string(),
+            };
+            if background.is_none() && opacity.is_none() {
+                out.push_str(&c);
+                continue;
+            }
+            out.push_str("<span style=\"");
+            if let Some(background) = background {
+                write!(out, "background:{};", background).unwrap();
+            }
+            if let Some(opacity) = opacity {
+                write!(out, "opacity:{:.2};", opacity).unwrap();
+            }
+            write!(out, "\">{}</span>", c).unwrap();
+        }
+        if row != Row::Symbols {
+            self.margin(last.map(|cell| cell.pos), out);
+        }
+        out.push('\n');
+    }
+}
+
+impl Renderer for HtmlRenderer<'_> {
+    fn block(&mut self, columns: &[Column], out: &mut String) {
+        for row in [Row::X, Row::Symbols, Row::Y] {
+            self.row(columns, row, out);
+        }
+        out.push('\n');
+    }
+
+    fn begin(&mut self, alignment: &Alignment, out: &mut String) {
+        self.width = alignment.xlen.max(alignment.ylen).to_string().len();
+        out.push_str("<pre class=\"alignment\">\n");
+    }
+
+    fn end(&mut self, out: &mut String) {
+        out.push_str("</pre>\n");
+    }
+}
+
+impl Alignment {
+    /// Lay out the alignment of x and y as a list of columns, including the
+    /// clipped parts of both sequences. This is the layout shared by all renderers.
+    pub fn columns(&self, x: TextSlice, y: TextSlice) -> Vec<Column> {
+        self.columns_of_runs(self.runs(), x, y)
+    }
+
+    // Lay out runs of operations, see `columns`.
+    pub(crate) fn columns_of_runs<I>(&self, runs: I, x: TextSlice, y: TextSlice) -> Vec<Column>
+    where
+        I: Iterator<Item = (AlignmentOperation, usize)>,
+    {
+        let mut columns = Vec::new();
+        let mut runs = runs.peekable();
+        if runs.peek().is_none() {
+            return columns;
+        }
+        let cell = |seq: TextSlice, pos: usize| {
+            Some(Cell {
+                pos,
+                base: seq[pos],
+            })
+        };
+        let clip_x = |columns: &mut Vec<Column>, start: usize, end: usize| {
+            columns.extend((start..end.min(x.len())).map(|pos| Column {
+                kind: ColumnKind::Clip,
+                x: cell(x, pos),
+                y: None,
+            }))
+        };
+        let clip_y = |columns: &mut Vec<Column>, start: usize, end: usize| {
+            columns.extend((start..end.min(y.len())).map(|pos| Column {
+                kind: ColumnKind::Clip,
+                x: None,
+                y: cell(y, pos),
+            }))
+        };
+
+        // In the standard modes, clipping is implicit.
+        if self.mode != AlignmentMode::Custom {
+            clip_x(&mut columns, 0, self.xstart);
+            clip_y(&mut columns, 0, self.ystart);
+        }
+        for (x_i, y_i, op) in self.positioned_runs(runs) {
+            let (kind, x_cell, y_cell) = match op {
+                AlignmentOperation::Match => (ColumnKind::Match, cell(x, x_i), cell(y, y_i)),
+                AlignmentOperation::Subst => (ColumnKind::Subst, cell(x, x_i), cell(y, y_i)),
+                AlignmentOperation::Ins => (ColumnKind::Ins, cell(x, x_i), None),
+                AlignmentOperation::Del => (ColumnKind::Del, None, cell(y, y_i)),
+                AlignmentOperation::RefSkip => (ColumnKind::Skip, None, cell(y, y_i)),
+                AlignmentOperation::Xclip(len) => {
+                    clip_x(&mut columns, x_i, x_i + len);
+                    continue;
+                }
+                AlignmentOperation::Yclip(len) => {
+                    clip_y(&mut columns, y_i, y_i + len);
+                    continue;
+                }
+            };
+            columns.push(Column {
+                kind,
+                x: x_cell,
+                y: y_cell,
+            });
+        }
+        if self.mode != AlignmentMode::Custom {
+            clip_x(&mut columns, self.xend, self.xlen);
+            clip_y(&mut columns, self.yend, self.ylen);
+        }
+        columns
+    }
+
+    /// Render the alignment of x and y with the given renderer, wrapping it into
+    /// blocks of `ncol` columns.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bio_types::alignment::{Alignment,AlignmentMode};
+    /// use bio_types::alignment::AlignmentOperation::*;
+    /// use bio_types::alignment::render::HtmlRenderer;
+    /// let alignment = Alignment {
+    ///     score: 1,
+    ///     xstart: 0,
+    ///     ystart: 2,
+    ///     xend: 3,
+    ///     yend: 5,
+    ///     ylen: 7,
+    ///     xlen: 3,
+    ///     operations: vec![Subst, Match, Match],
+    ///     mode: AlignmentMode::Semiglobal,
+    /// };
+    /// let html = alignment.render(b"GAT", b"CTAATCC", 100, &mut HtmlRenderer::new());
+    /// assert_eq!(
+    ///     html,
+    ///     concat!(
+    ///         "<pre class=\"alignment\">\n",
+    ///         " 1   <span style=\"background:#f99;\">G</span>AT   3 \n",
+    ///         "     <span style=\"background:#f99;\">\\</span>||  \n",
+    ///         " 1 CT<span style=\"background:#f99;\">A</span>ATCC 7 \n",
+    ///         "\n",
+    ///         "</pre>\n"
+    ///     )
+    /// );
+    /// ```
+    pub fn render<R: Renderer>(
+        &self,
+        x: TextSlice,
+        y: TextSlice,
+        ncol: usize,
+        renderer: &mut R,
+    ) -> String {
+        self.render_columns(&self.columns(x, y), ncol, renderer)
+    }
+
+    // Render laid out columns, see `render`.
+    pub(crate) fn render_columns<R: Renderer>(
+        &self,
+        columns: &[Column],
+        ncol: usize,
+        renderer: &mut R,
+    ) -> String {
+        let mut out = String::new();
+        renderer.begin(self, &mut out);
+        for block in columns.chunks(ncol) {
+            renderer.block(block, &mut out);
+        }
+        renderer.end(&mut out);
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alignment::AlignmentOperation::*;
+
+    struct Read(Vec<u8>);
+
+    impl SequenceRead for Read {
+        fn name(&self) -> &[u8] {
+            b"read"
+        }
+        fn base(&self, i: usize) -> u8 {
+            b"ACGT"[i]
+        }
+        fn base_qual(&self, i: usize) -> u8 {
+            self.0[i]
+        }
+        fn len(&self) -> usize {
+            self.0.len()
+        }
+    }
+
+    fn alignment() -> Alignment {
+        Alignment {
+            score: 0,
+            xstart: 1,
+            ystart: 0,
+            xend: 4,
+            yend: 3,
+            ylen: 3,
+            xlen: 4,
+            operations: vec![Xclip(1), Match, Ins, Subst, Del],
+            mode: AlignmentMode::Custom,
+        }
+    }
+
+    #[test]
+    fn test_columns() {
+        let columns = alignment().columns(b"ACGT", b"CTA");
+        assert_eq!(columns.len(), 5);
+        assert_eq!(columns[0].x, Some(Cell { pos: 0, base: b'A' }));
+        assert_eq!(columns[2].kind, ColumnKind::Ins);
+        assert_eq!(columns[4].x, None);
+        assert_eq!(columns[4].y, Some(Cell { pos: 2, base: b'A' }));
+        assert_eq!(
+            alignment().pretty(b"ACGT", b"CTA", 3),
+            "ACG\n |+\n C-\n\n\nT-\n\\x\nTA\n\n\n"
+        );
+    }
+
+    #[test]
+    fn test_pretty_trailing_clip() {
+        // trailing clips show the end of the sequences, not their first bases
+        let alignment = Alignment {
+            score: 0,
+            xstart: 3,
+            ystart: 4,
+            xend: 10,
+            yend: 9,
+            ylen: 9,
+            xlen: 13,
+            operations: vec![
+                Xclip(3),
+                Yclip(4),
+                Ins,
+                Ins,
+                Match,
+                Match,
+                Subst,
+                Match,
+                Match,
+                Xclip(3),
+            ],
+            mode: AlignmentMode::Custom,
+        };
+        let pretty = alignment.pretty(b"GAACTCCGGAGAG", b"TTTTCCTGA", 100);
+        assert_eq!(
+            pretty,
+            "GAA    CTCCGGAGAG\n       ++||\\||   \n   TTTT--CCTGA   \n\n\n"
+        );
+    }
+
+    #[test]
+    fn test_renderers() {
+        let read = Read(vec![0, 40, 20, 60]);
+        let ansi = alignment().render(b"ACGT", b"CTA", 100, &mut AnsiRenderer::with_read(&read));
+        let x_row = ansi.lines().next().unwrap();
+        assert_eq!(
+            x_row,
+            concat!(
+                "\x1b[2m\x1b[38;5;236mA\x1b[0m",
+                "\x1b[38;5;255mC\x1b[0m",
+                "\x1b[43m\x1b[38;5;245mG\x1b[0m",
+                "\x1b[41m\x1b[38;5;255mT\x1b[0m",
+                "\x1b[43m-\x1b[0m"
+            )
+        );
+
+        let html = alignment().render(b"ACGT", b"CTA", 100, &mut HtmlRenderer::with_read(&read));
+        let rows: Vec<_> = html.lines().collect();
+        assert_eq!(
+            rows[1],
+            concat!(
+                " 1 <span style=\"opacity:0.30;\">A</span>",
+                "<span style=\"opacity:1.00;\">C</span>",
+                "<span style=\"background:#fd6;opacity:0.65;\">G</span>",
+                "<span style=\"background:#f99;opacity:1.00;\">T</span>",
+                "<span style=\"background:#fd6;\">-</span> 4 "
+            )
+        );
+        assert_eq!(
+            rows[3],
+            concat!(
+                " 1  C<span style=\"background:#fd6;\">-</span>",
+                "<span style=\"background:#f99;\">T</span>",
+                "<span style=\"background:#fd6;\">A</span> 3 "
+            )
+        );
+    }
+}
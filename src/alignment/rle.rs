@@ -0,0 +1,270 @@
+//! Run-length encoded storage of alignment operations, for long alignments (e.g.
+//! of ultra-long reads or whole genomes) where storing one operation per aligned
+//! base would take too much memory.
+
+use std::iter::FromIterator;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::render::Renderer;
+use super::{Alignment, AlignmentMode, AlignmentOperation, Cigar, CigarError, TextSlice};
+
+/// Run-length encoded alignment operations. Adjacent equal operations are merged
+/// into runs; clip operations keep their own length.
+///
+/// # Example
+///
+/// ```
+/// use bio_types::alignment::AlignmentOperation::*;
+/// use bio_types::alignment::rle::RleOperations;
+/// let ops = vec![Xclip(2), Match, Match, Match, Subst, Ins, Ins, Match];
+/// let rle = RleOperations::from(&ops[..]);
+/// assert_eq!(rle.runs(), [(Xclip(2), 1), (Match, 3), (Subst, 1), (Ins, 2), (Match, 1)]);
+/// assert_eq!(rle.len(), 8);
+/// assert_eq!(Vec::from(&rle), ops);
+/// ```
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Default)]
+pub struct RleOperations {
+    runs: Vec<(AlignmentOperation, usize)>,
+    len: usize,
+}
+
+impl RleOperations {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append a single operation.
+    pub fn push(&mut self, op: AlignmentOperation) {
+        self.push_run(op, 1);
+    }
+
+    /// Append `len` times the given operation. Empty runs are ignored.
+    pub fn push_run(&mut self, op: AlignmentOperation, len: usize) {
+        if len == 0 {
+            return;
+        }
+        match self.runs.last_mut() {
+            Some((last, n)) if *last == op => *n += len,
+            _ => self.runs.push((op, len)),
+        }
+        self.len += len;
+    }
+
+    /// The runs of operations, with their lengths.
+    pub fn runs(&self) -> &[(AlignmentOperation, usize)] {
+        &self.runs
+    }
+
+    /// Number of operations.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Iterate over the individual operations.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = AlignmentOperation> + '_ {
+        self.runs
+            .iter()
+            .flat_map(|&(op, len)| std::iter::repeat(op).take(len))
+    }
+
+    fn iter_runs(
+        &self,
+    ) -> impl DoubleEndedIterator<Item = (AlignmentOperation, usize)> + Clone + '_ {
+        self.runs.iter().cloned()
+    }
+}
+
+impl FromIterator<AlignmentOperation> for RleOperations {
+    fn from_iter<I: IntoIterator<Item = AlignmentOperation>>(iter: I) -> Self {
+        let mut ops = RleOperations::new();
+        for op in iter {
+            ops.push(op);
+        }
+        ops
+    }
+}
+
+impl From<&[AlignmentOperation]> for RleOperations {
+    fn from(operations: &[AlignmentOperation]) -> Self {
+        operations.iter().cloned().collect()
+    }
+}
+
+impl From<&RleOperations> for Vec<AlignmentOperation> {
+    fn from(operations: &RleOperations) -> Self {
+        operations.iter().collect()
+    }
+}
+
+/// An alignment with run-length encoded operations. Apart from the operations, the
+/// fields are the same as those of [`Alignment`](../struct.Alignment.html), and
+/// conversions in both directions are lossless.
+///
+/// # Example
+///
+/// ```
+/// use bio_types::alignment::{Alignment,AlignmentMode};
+/// use bio_types::alignment::rle::RleAlignment;
+/// let alignment = Alignment::from_cigar("3S3=1X2I2D1S", 5, 10, 20).unwrap();
+/// let compact = RleAlignment::from(&alignment);
+/// assert_eq!(compact.operations.runs().len(), 8);
+/// assert_eq!(compact.cigar(false).unwrap(), "3S3=1X2I2D1S");
+/// assert_eq!(compact.path(), alignment.path());
+/// assert_eq!(Alignment::from(&compact), alignment);
+/// ```
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Eq, PartialEq, Clone, Default)]
+pub struct RleAlignment {
+    pub score: i32,
+    pub ystart: usize,
+    pub xstart: usize,
+    pub yend: usize,
+    pub xend: usize,
+    pub ylen: usize,
+    pub xlen: usize,
+    pub operations: RleOperations,
+    pub mode: AlignmentMode,
+}
+
+impl RleAlignment {
+    // The alignment without its operations.
+    fn header(&self) -> Alignment {
+        Alignment {
+            score: self.score,
+            ystart: self.ystart,
+            xstart: self.xstart,
+            yend: self.yend,
+            xend: self.xend,
+            ylen: self.ylen,
+            xlen: self.xlen,
+            operations: Vec::new(),
+            mode: self.mode,
+        }
+    }
+
+    /// Calculate the CIGAR string, see
+    /// [`Alignment::cigar`](../struct.Alignment.html#method.cigar).
+    pub fn cigar(&self, hard_clip: bool) -> Result<String, CigarError> {
+        let cigar = self.to_cigar(hard_clip)?;
+        if cigar.is_empty() {
+            Ok(String::new())
+        } else {
+            Ok(cigar.to_string())
+        }
+    }
+
+    /// Calculate the typed CIGAR, see
+    /// [`Alignment::to_cigar`](../struct.Alignment.html#method.to_cigar).
+    pub fn to_cigar(&self, hard_clip: bool) -> Result<Cigar, CigarError> {
+        self.header()
+            .cigar_of_runs(self.operations.iter_runs(), hard_clip)
+    }
+
+    /// Return the optimal path in the alignment matrix, see
+    /// [`Alignment::path`](../struct.Alignment.html#method.path).
+    pub fn path(&self) -> Vec<(usize, usize, AlignmentOperation)> {
+        self.header().path_of_runs(self.operations.iter_runs())
+    }
+
+    /// Return the pretty formatted alignment, see
+    /// [`Alignment::pretty`](../struct.Alignment.html#method.pretty).
+    pub fn pretty(&self, x: TextSlice, y: TextSlice, ncol: usize) -> String {
+        self.render(x, y, ncol, &mut super::render::PlainRenderer)
+    }
+
+    /// Render the alignment with the given renderer, see
+    /// [`Alignment::render`](../struct.Alignment.html#method.render).
+    pub fn render<R: Renderer>(
+        &self,
+        x: TextSlice,
+        y: TextSlice,
+        ncol: usize,
+        renderer: &mut R,
+    ) -> String {
+        let header = self.header();
+        let columns = header.columns_of_runs(self.operations.iter_runs(), x, y);
+        header.render_columns(&columns, ncol, renderer)
+    }
+
+    /// Number of bases in reference sequence that are aligned
+    pub fn y_aln_len(&self) -> usize {
+        self.yend - self.ystart
+    }
+
+    /// Number of bases in query sequence that are aligned
+    pub fn x_aln_len(&self) -> usize {
+        self.xend - self.xstart
+    }
+}
+
+impl From<&Alignment> for RleAlignment {
+    fn from(alignment: &Alignment) -> Self {
+        RleAlignment {
+            score: alignment.score,
+            ystart: alignment.ystart,
+            xstart: alignment.xstart,
+            yend: alignment.yend,
+            xend: alignment.xend,
+            ylen: alignment.ylen,
+            xlen: alignment.xlen,
+            operations: RleOperations::from(&alignment.operations[..]),
+            mode: alignment.mode,
+        }
+    }
+}
+
+impl From<&RleAlignment> for Alignment {
+    fn from(alignment: &RleAlignment) -> Self {
+        Alignment {
+            operations: Vec::from(&alignment.operations),
+            ..alignment.header()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alignment::AlignmentOperation::*;
+
+    #[test]
+    fn test_rle_alignment() {
+        let alignment = Alignment {
+            score: 5,
+            xstart: 0,
+            ystart: 5,
+            xend: 4,
+            yend: 10,
+            ylen: 10,
+            xlen: 5,
+            operations: vec![Yclip(5), Match, Subst, Subst, Ins, Del, Del, Xclip(1)],
+            mode: AlignmentMode::Custom,
+        };
+        let compact = RleAlignment::from(&alignment);
+        assert_eq!(compact.operations.runs().len(), 6);
+        assert_eq!(compact.operations.iter().next_back(), Some(Xclip(1)));
+        assert_eq!(compact.path(), alignment.path());
+        assert_eq!(compact.to_cigar(true), alignment.to_cigar(true));
+        assert_eq!(
+            compact.pretty(b"AAAAA", b"TTTTTTTTTT", 4),
+            alignment.pretty(b"AAAAA", b"TTTTTTTTTT", 4)
+        );
+        assert_eq!(compact.x_aln_len(), 4);
+        assert_eq!(Alignment::from(&compact), alignment);
+
+        let mut ops = RleOperations::new();
+        ops.push_run(Match, 100_000);
+        ops.push_run(Ins, 0);
+        ops.push(Match);
+        assert_eq!(ops.runs(), [(Match, 100_001)]);
+        assert!(RleAlignment::default().operations.is_empty());
+        assert_eq!(RleAlignment::default().cigar(false), Ok(String::new()));
+    }
+}
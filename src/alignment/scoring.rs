@@ -0,0 +1,268 @@
+//! Scoring schemes for recomputing the score of an alignment, modelled after the
+//! ones used by the pairwise aligners of rust-bio.
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::{Alignment, AlignmentOperation, TextSlice};
+
+/// Trait for scoring a pair of aligned bases.
+pub trait MatchFunc {
+    fn score(&self, a: u8, b: u8) -> i32;
+}
+
+/// A simple scoring of aligned bases with a fixed match and mismatch score.
+/// Bases are compared case-sensitively.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Hash)]
+pub struct MatchParams {
+    pub match_score: i32,
+    pub mismatch_score: i32,
+}
+
+impl MatchParams {
+    /// Create new match parameters. The match score should be positive and the
+    /// mismatch score negative.
+    pub fn new(match_score: i32, mismatch_score: i32) -> Self {
+        MatchParams {
+            match_score,
+            mismatch_score,
+        }
+    }
+}
+
+impl MatchFunc for MatchParams {
+    #[inline]
+    fn score(&self, a: u8, b: u8) -> i32 {
+        if a == b {
+            self.match_score
+        } else {
+            self.mismatch_score
+        }
+    }
+}
+
+/// Any function of two bases can be used as match function, e.g. a substitution
+/// matrix like BLOSUM62.
+impl<F> MatchFunc for F
+where
+    F: Fn(u8, u8) -> i32,
+{
+    #[inline]
+    fn score(&self, a: u8, b: u8) -> i32 {
+        (self)(a, b)
+    }
+}
+
+/// A scoring scheme with affine gap penalties and clip penalties. A gap of length
+/// `k` scores `gap_open + k * gap_extend`, so both penalties should be negative.
+/// The clip penalties are applied once per clipped prefix or suffix in `Custom`
+/// mode alignments, irrespective of the clipped length. In the standard modes,
+/// clipping is implicit and not penalized.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone)]
+pub struct Scoring<F: MatchFunc> {
+    pub gap_open: i32,
+    pub gap_extend: i32,
+    pub match_fn: F,
+    pub xclip_prefix: i32,
+    pub xclip_suffix: i32,
+    pub yclip_prefix: i32,
+    pub yclip_suffix: i32,
+}
+
+impl Scoring<MatchParams> {
+    /// Create a scoring scheme from gap penalties and match and mismatch scores.
+    /// Clipping is not penalized.
+    pub fn from_scores(
+        gap_open: i32,
+        gap_extend: i32,
+        match_score: i32,
+        mismatch_score: i32,
+    ) -> Self {
+        Scoring::new(
+            gap_open,
+            gap_extend,
+            MatchParams::new(match_score, mismatch_score),
+        )
+    }
+}
+
+impl<F: MatchFunc> Scoring<F> {
+    /// Create a scoring scheme from gap penalties and a match function. Clipping is
+    /// not penalized.
+    pub fn new(gap_open: i32, gap_extend: i32, match_fn: F) -> Self {
+        Scoring {
+            gap_open,
+            gap_extend,
+            match_fn,
+            xclip_prefix: 0,
+            xclip_suffix: 0,
+            yclip_prefix: 0,
+            yclip_suffix: 0,
+        }
+    }
+
+    /// Set the penalty for clipping a prefix or suffix of x.
+    pub fn xclip(mut self, penalty: i32) -> Self {
+        self.xclip_prefix = penalty;
+        self.xclip_suffix = penalty;
+        self
+    }
+
+    /// Set the penalty for clipping a prefix or suffix of y.
+    pub fn yclip(mut self, penalty: i32) -> Self {
+        self.yclip_prefix = penalty;
+        self.yclip_suffix = penalty;
+        self
+    }
+
+    /// Set the penalty for clipping a prefix of x.
+    pub fn xclip_prefix(mut self, penalty: i32) -> Self {
+        self.xclip_prefix = penalty;
+        self
+    }
+
+    /// Set the penalty for clipping a suffix of x.
+    pub fn xclip_suffix(mut self, penalty: i32) -> Self {
+        self.xclip_suffix = penalty;
+        self
+    }
+
+    /// Set the penalty for clipping a prefix of y.
+    pub fn yclip_prefix(mut self, penalty: i32) -> Self {
+        self.yclip_prefix = penalty;
+        self
+    }
+
+    /// Set the penalty for clipping a suffix of y.
+    pub fn yclip_suffix(mut self, penalty: i32) -> Self {
+        self.yclip_suffix = penalty;
+        self
+    }
+}
+
+impl Alignment {
+    /// Recompute the score of the alignment from its operations, given the query
+    /// sequence x, the reference sequence y and a scoring scheme. Aligned bases are
+    /// scored by the match function, irrespective of whether they are marked as
+    /// `Match` or `Subst`, and reference skips are not scored. The stored `score` is
+    /// left untouched; to update it, use
+    /// `alignment.score = alignment.rescore(x, y, &scoring)`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bio_types::alignment::{Alignment,AlignmentMode};
+    /// use bio_types::alignment::AlignmentOperation::*;
+    /// use bio_types::alignment::scoring::Scoring;
+    /// let x = b"ACGTTACGA";
+    /// let y = b"ACGATACCCGA";
+    /// let alignment = Alignment {
+    ///     score: 0,
+    ///     xstart: 0,
+    ///     ystart: 0,
+    ///     xend: 9,
+    ///     yend: 11,
+    ///     ylen: 11,
+    ///     xlen: 9,
+    ///     operations: vec![Match, Match, Match, Subst, Match, Match, Match, Del, Del, Match, Match],
+    ///     mode: AlignmentMode::Global,
+    /// };
+    /// let scoring = Scoring::from_scores(-5, -1, 1, -1);
+    /// assert_eq!(alignment.rescore(x, y, &scoring), 8 - 1 - 7);
+    /// // a substitution matrix
+    /// let scoring = Scoring::new(-5, -1, |a: u8, b: u8| if a == b { 2 } else { -3 });
+    /// assert_eq!(alignment.rescore(x, y, &scoring), 16 - 3 - 7);
+    /// ```
+    pub fn rescore<F: MatchFunc>(&self, x: TextSlice, y: TextSlice, scoring: &Scoring<F>) -> i32 {
+        let mut score = 0;
+        let mut last = None;
+        let mut aligned = false;
+        for (x_i, y_i, op) in self.positioned_operations() {
+            score += match op {
+                AlignmentOperation::Match | AlignmentOperation::Subst => {
+                    scoring.match_fn.score(x[x_i], y[y_i])
+                }
+                AlignmentOperation::Ins | AlignmentOperation::Del => {
+                    if last == Some(op) {
+                        scoring.gap_extend
+                    } else {
+                        scoring.gap_open + scoring.gap_extend
+                    }
+                }
+                AlignmentOperation::RefSkip
+                | AlignmentOperation::Xclip(0)
+                | AlignmentOperation::Yclip(0) => 0,
+                AlignmentOperation::Xclip(_) if aligned => scoring.xclip_suffix,
+                AlignmentOperation::Xclip(_) => scoring.xclip_prefix,
+                AlignmentOperation::Yclip(_) if aligned => scoring.yclip_suffix,
+                AlignmentOperation::Yclip(_) => scoring.yclip_prefix,
+            };
+            if !matches!(
+                op,
+                AlignmentOperation::Xclip(_) | AlignmentOperation::Yclip(_)
+            ) {
+                aligned = true;
+            }
+            last = Some(op);
+        }
+        score
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alignment::AlignmentMode;
+    use crate::alignment::AlignmentOperation::*;
+
+    #[test]
+    fn test_rescore() {
+        let x = b"GGACGTTCGT";
+        let y = b"TTTACCAAGGTA";
+        let alignment = Alignment {
+            score: -11,
+            xstart: 2,
+            ystart: 3,
+            xend: 10,
+            yend: 11,
+            ylen: 12,
+            xlen: 10,
+            operations: vec![
+                Yclip(3),
+                Xclip(2),
+                Match,
+                Match,
+                Subst,
+                Subst,
+                Del,
+                Subst,
+                Ins,
+                Match,
+                Match,
+                Yclip(1),
+            ],
+            mode: AlignmentMode::Custom,
+        };
+        let scoring = Scoring::from_scores(-5, -1, 1, -1);
+        assert_eq!(alignment.rescore(x, y, &scoring), 4 - 3 - 12);
+
+        let scoring = scoring.xclip_prefix(-2).xclip_suffix(-100).yclip(-3);
+        assert_eq!(alignment.rescore(x, y, &scoring), 4 - 3 - 12 - 2 - 6);
+
+        // an insertion after a deletion opens a new gap
+        let alignment = Alignment {
+            score: -10,
+            xstart: 0,
+            ystart: 0,
+            xend: 3,
+            yend: 3,
+            ylen: 3,
+            xlen: 3,
+            operations: vec![Match, Del, Ins, Match],
+            mode: AlignmentMode::Global,
+        };
+        assert_eq!(alignment.rescore(b"ATC", b"AGC", &scoring), -10);
+    }
+}
@@ -0,0 +1,272 @@
+//! Extraction of sub-alignments and concatenation of adjacent alignments.
+
+use std::ops::Range;
+
+use thiserror::Error;
+
+use super::{Alignment, AlignmentMode, AlignmentOperation};
+
+impl Alignment {
+    /// Cut the alignment down to the window `range` of the reference sequence y.
+    /// The result contains all alignment columns at reference positions inside the
+    /// window, and the insertions between them. Insertions at the boundaries of the
+    /// window are not included. `None` is returned if no reference position of the
+    /// window is part of the alignment.
+    ///
+    /// Start and end positions are adjusted accordingly. A sliced `Custom` mode
+    /// alignment has its clip operations adjusted as well, sliced alignments in the
+    /// standard modes are in `Local` mode. The score is left undefined (i.e. set to
+    /// zero); it can be recomputed with [`rescore`](#method.rescore).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bio_types::alignment::{Alignment,AlignmentMode};
+    /// use bio_types::alignment::AlignmentOperation::*;
+    /// let alignment = Alignment::from_cigar("3S3=1X2I2D1S", 5, 10, 20).unwrap();
+    /// let sliced = alignment.slice_y(7..10).unwrap();
+    /// assert_eq!((sliced.ystart, sliced.yend), (7, 10));
+    /// assert_eq!((sliced.xstart, sliced.xend), (5, 9));
+    /// assert_eq!(sliced.cigar(false).unwrap(), "5S1=1X2I1D1S");
+    /// assert_eq!(
+    ///     sliced.operations,
+    ///     [Yclip(7), Xclip(5), Match, Subst, Ins, Ins, Del, Xclip(1), Yclip(10)]
+    /// );
+    /// assert_eq!(alignment.slice_y(0..5), None);
+    /// ```
+    pub fn slice_y(&self, range: Range<usize>) -> Option<Alignment> {
+        self.slice(range, false)
+    }
+
+    /// Cut the alignment down to the window `range` of the query sequence x. This
+    /// works like [`slice_y`](#method.slice_y), with the roles of x and y swapped,
+    /// i.e. deletions at the boundaries of the window are not included.
+    pub fn slice_x(&self, range: Range<usize>) -> Option<Alignment> {
+        self.slice(range, true)
+    }
+
+    fn slice(&self, range: Range<usize>, along_x: bool) -> Option<Alignment> {
+        let mut operations = Vec::new();
+        let mut start = None;
+        // gaps in the sliced sequence, included once followed by an aligned base
+        let mut gaps = Vec::new();
+
+        for (x_i, y_i, op) in self.positioned_operations() {
+            let pos = if along_x { x_i } else { y_i };
+            let consumes = match (op, along_x) {
+                (AlignmentOperation::Match | AlignmentOperation::Subst, _)
+                | (AlignmentOperation::Ins, true)
+                | (AlignmentOperation::Del | AlignmentOperation::RefSkip, false) => true,
+                (AlignmentOperation::Ins, false)
+                | (AlignmentOperation::Del | AlignmentOperation::RefSkip, true) => false,
+                _ => continue,
+            };
+            if consumes {
+                if range.contains(&pos) {
+                    if start.is_none() {
+                        start = Some((x_i, y_i));
+                    }
+                    operations.append(&mut gaps);
+                    operations.push(op);
+                } else {
+                    gaps.clear();
+                }
+            } else if start.is_some() && pos < range.end {
+                gaps.push(op);
+            }
+        }
+
+        let (xstart, ystart) = start?;
+        let mode = match self.mode {
+            AlignmentMode::Custom => AlignmentMode::Custom,
+            _ => AlignmentMode::Local,
+        };
+        Some(self.with_aligned_operations(operations, xstart, ystart, mode, 0))
+    }
+
+    /// Concatenate this alignment with an adjacent one, i.e. one that starts in
+    /// both sequences exactly where this alignment ends. This is e.g. useful to
+    /// join alignments that have been sliced before. The score of the result is the
+    /// sum of both scores, which is only exact if no gap spans the junction.
+    ///
+    /// The result is in `Custom` mode if one of the alignments is, and in `Local`
+    /// mode otherwise.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bio_types::alignment::Alignment;
+    /// let alignment = Alignment::from_cigar("3S3=1X2I2D1S", 5, 10, 20).unwrap();
+    /// let left = alignment.slice_y(5..8).unwrap();
+    /// let right = alignment.slice_y(8..11).unwrap();
+    /// assert_eq!(left.concat(&right).unwrap(), alignment);
+    /// assert!(right.concat(&left).is_err());
+    /// ```
+    pub fn concat(&self, other: &Alignment) -> Result<Alignment, ConcatError> {
+        if self.xlen != other.xlen || self.ylen != other.ylen {
+            return Err(ConcatError::LengthMismatch);
+        }
+        if self.xend != other.xstart || self.yend != other.ystart {
+            return Err(ConcatError::NotContiguous {
+                xend: self.xend,
+                yend: self.yend,
+                xstart: other.xstart,
+                ystart: other.ystart,
+            });
+        }
+        let is_aligned = |op: &&AlignmentOperation| {
+            !matches!(
+                op,
+                AlignmentOperation::Xclip(_) | AlignmentOperation::Yclip(_)
+            )
+        };
+        let operations = self
+            .operations
+            .iter()
+            .filter(is_aligned)
+            .chain(other.operations.iter().filter(is_aligned))
+            .cloned()
+            .collect();
+        let mode = if self.mode == AlignmentMode::Custom || other.mode == AlignmentMode::Custom {
+            AlignmentMode::Custom
+        } else {
+            AlignmentMode::Local
+        };
+        Ok(self.with_aligned_operations(
+            operations,
+            self.xstart,
+            self.ystart,
+            mode,
+            self.score + other.score,
+        ))
+    }
+
+    // Build an alignment of the same sequences from operations without clips, adding
+    // the clips in case of `Custom` mode.
+    pub(super) fn with_aligned_operations(
+        &self,
+        aligned: Vec<AlignmentOperation>,
+        xstart: usize,
+        ystart: usize,
+        mode: AlignmentMode,
+        score: i32,
+    ) -> Alignment {
+        let mut xend = xstart;
+        let mut yend = ystart;
+        for op in aligned.iter() {
+            match op {
+                AlignmentOperation::Match | AlignmentOperation::Subst => {
+                    xend += 1;
+                    yend += 1;
+                }
+                AlignmentOperation::Ins => xend += 1,
+                AlignmentOperation::Del | AlignmentOperation::RefSkip => yend += 1,
+                _ => {}
+            }
+        }
+
+        let operations = if mode == AlignmentMode::Custom {
+            let mut operations = Vec::with_capacity(aligned.len() + 4);
+            if ystart > 0 {
+                operations.push(AlignmentOperation::Yclip(ystart));
+            }
+            if xstart > 0 {
+                operations.push(AlignmentOperation::Xclip(xstart));
+            }
+            operations.extend(aligned);
+            if xend < self.xlen {
+                operations.push(AlignmentOperation::Xclip(self.xlen - xend));
+            }
+            if yend < self.ylen {
+                operations.push(AlignmentOperation::Yclip(self.ylen - yend));
+            }
+            operations
+        } else {
+            aligned
+        };
+
+        Alignment {
+            score,
+            ystart,
+            xstart,
+            yend,
+            xend,
+            ylen: self.ylen,
+            xlen: self.xlen,
+            operations,
+            mode,
+        }
+    }
+}
+
+/// Errors that arise when concatenating alignments.
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum ConcatError {
+    #[error("alignments refer to sequences of different lengths")]
+    LengthMismatch,
+    #[error(
+        "alignments are not contiguous: first one ends at ({xend}, {yend}), second one starts at ({xstart}, {ystart})"
+    )]
+    NotContiguous {
+        xend: usize,
+        yend: usize,
+        xstart: usize,
+        ystart: usize,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alignment::AlignmentOperation::*;
+
+    #[test]
+    fn test_slice() {
+        let alignment = Alignment {
+            score: 5,
+            xstart: 1,
+            ystart: 2,
+            xend: 9,
+            yend: 9,
+            ylen: 10,
+            xlen: 10,
+            operations: vec![Match, Ins, Subst, Del, Del, Match, Ins, Ins, Match, Match],
+            mode: AlignmentMode::Semiglobal,
+        };
+
+        let sliced = alignment.slice_y(3..6).unwrap();
+        assert_eq!(sliced.operations, [Subst, Del, Del]);
+        assert_eq!((sliced.xstart, sliced.xend), (3, 4));
+        assert_eq!((sliced.ystart, sliced.yend), (3, 6));
+        assert_eq!(sliced.mode, AlignmentMode::Local);
+        assert_eq!(sliced.score, 0);
+
+        let sliced = alignment.slice_x(1..6).unwrap();
+        assert_eq!(sliced.operations, [Match, Ins, Subst, Del, Del, Match, Ins]);
+        assert_eq!((sliced.ystart, sliced.yend), (2, 7));
+
+        let sliced = alignment.slice_x(4..20).unwrap();
+        assert_eq!(sliced.operations, [Match, Ins, Ins, Match, Match]);
+        assert_eq!((sliced.ystart, sliced.yend), (6, 9));
+        assert_eq!(alignment.slice_x(0..1), None);
+
+        let left = alignment.slice_y(0..4).unwrap();
+        let right = alignment.slice_y(4..10).unwrap();
+        let joined = left.concat(&right).unwrap();
+        assert_eq!(joined.operations, alignment.operations);
+        assert_eq!(joined.path(), alignment.path());
+
+        let mut other = right.clone();
+        other.xlen += 1;
+        assert_eq!(left.concat(&other), Err(ConcatError::LengthMismatch));
+        assert_eq!(
+            left.concat(&alignment.slice_y(5..10).unwrap()),
+            Err(ConcatError::NotContiguous {
+                xend: 4,
+                yend: 4,
+                xstart: 4,
+                ystart: 5
+            })
+        );
+    }
+}
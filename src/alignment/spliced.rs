@@ -0,0 +1,94 @@
+//! Conversion of spliced alignments, e.g. of RNA-seq reads, into spliced
+//! annotation locations.
+
+use super::{Alignment, AlignmentOperation};
+use crate::annot::spliced::{Spliced, SplicingError};
+
+impl Alignment {
+    /// The exon blocks of the alignment, as a spliced location on the reference.
+    /// `start` is the position of the first base of y on the reference sequence
+    /// `refid`. Each exon covers a maximal stretch of aligned reference bases
+    /// (including deletions), and exons are separated by the reference skips.
+    ///
+    /// An error is returned if the alignment does not cover any reference base.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bio_types::alignment::Alignment;
+    /// use bio_types::annot::loc::Loc;
+    /// use bio_types::strand::ReqStrand;
+    /// let alignment = Alignment::from_cigar("2S10M500N5M1D5M", 100, 22, 1000).unwrap();
+    /// let spliced = alignment
+    ///     .to_spliced("chrV", 166_000, ReqStrand::Forward)
+    ///     .unwrap();
+    /// assert_eq!(spliced.start(), 166_100);
+    /// assert_eq!(spliced.exon_starts(), [0, 510]);
+    /// assert_eq!(spliced.exon_lengths(), [10, 11]);
+    /// ```
+    pub fn to_spliced<R, S>(
+        &self,
+        refid: R,
+        start: isize,
+        strand: S,
+    ) -> Result<Spliced<R, S>, SplicingError> {
+        let mut blocks: Vec<(usize, usize)> = Vec::new();
+        for (_, y_i, op) in self.positioned_operations() {
+            if !matches!(
+                op,
+                AlignmentOperation::Match | AlignmentOperation::Subst | AlignmentOperation::Del
+            ) {
+                continue;
+            }
+            match blocks.last_mut() {
+                Some((_, end)) if *end == y_i => *end += 1,
+                _ => blocks.push((y_i, y_i + 1)),
+            }
+        }
+        let first = blocks.first().ok_or(SplicingError::NoExons)?.0;
+        let lengths: Vec<_> = blocks.iter().map(|(start, end)| end - start).collect();
+        let starts: Vec<_> = blocks.iter().map(|(start, _)| start - first).collect();
+        Spliced::with_lengths_starts(refid, start + first as isize, &lengths, &starts, strand)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alignment::AlignmentMode;
+    use crate::alignment::AlignmentOperation::*;
+    use crate::strand::NoStrand;
+
+    #[test]
+    fn test_to_spliced() {
+        // skips around an insertion are merged into one intron, leading and
+        // trailing skips are dropped
+        let alignment = Alignment {
+            score: 0,
+            xstart: 0,
+            ystart: 2,
+            xend: 4,
+            yend: 11,
+            ylen: 12,
+            xlen: 4,
+            operations: vec![
+                RefSkip, Match, Del, RefSkip, RefSkip, Ins, RefSkip, Match, Subst, RefSkip,
+            ],
+            mode: AlignmentMode::Local,
+        };
+        assert_eq!(alignment.path().last(), Some(&(4, 11, RefSkip)));
+        assert_eq!(alignment.cigar(false).unwrap(), "1N1=1D2N1I1N1=1X1N");
+        assert_eq!(
+            alignment.pretty(b"ACGT", b"CCCACCTTAGTC", 20),
+            "  -A---C-GT- \n  .|x..+.|\\. \nCCCACCT-TAGTC\n\n\n"
+        );
+        let spliced = alignment.to_spliced("chr1", 10, NoStrand::Unknown).unwrap();
+        assert_eq!(spliced.to_string(), "chr1:13-15;18-20");
+
+        let alignment = Alignment::from_cigar("3I", 0, 3, 5).unwrap();
+        assert!(matches!(
+            alignment.to_spliced("chr1", 0, NoStrand::Unknown),
+            Err(SplicingError::NoExons)
+        ));
+    }
+}
@@ -0,0 +1,158 @@
+//! Summary statistics of pairwise alignments.
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::{Alignment, AlignmentOperation};
+
+/// Summary statistics of an alignment, as returned by
+/// [`Alignment::stats`](../struct.Alignment.html#method.stats). Identities and
+/// coverages are fractions between 0 and 1, and 0 if undefined.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, PartialEq, Copy, Clone, Default)]
+pub struct AlignmentStats {
+    /// Number of `Match` operations
+    pub matches: usize,
+    /// Number of `Subst` operations
+    pub mismatches: usize,
+    /// Number of inserted bases
+    pub insertions: usize,
+    /// Number of deleted bases
+    pub deletions: usize,
+    /// Number of skipped reference bases, e.g. introns, which are not counted as
+    /// edits
+    pub skipped: usize,
+    /// Number of runs of consecutive insertions
+    pub insertion_opens: usize,
+    /// Number of runs of consecutive deletions
+    pub deletion_opens: usize,
+    /// Edit distance, i.e. the SAM `NM` tag
+    pub edit_distance: usize,
+    /// Matches divided by the number of alignment columns
+    pub blast_identity: f64,
+    /// Matches divided by the number of alignment columns, counting each run of
+    /// gaps as a single column
+    pub gap_compressed_identity: f64,
+    /// Fraction of the query sequence x covered by the alignment
+    pub query_coverage: f64,
+    /// Fraction of the reference sequence y covered by the alignment, excluding
+    /// skipped bases
+    pub reference_coverage: f64,
+}
+
+impl AlignmentStats {
+    /// Total number of gap openings.
+    pub fn gap_opens(&self) -> usize {
+        self.insertion_opens + self.deletion_opens
+    }
+}
+
+fn fraction(numerator: usize, denominator: usize) -> f64 {
+    if denominator == 0 {
+        0.0
+    } else {
+        numerator as f64 / denominator as f64
+    }
+}
+
+impl Alignment {
+    /// Calculate summary statistics of the alignment. Since the sequences are not
+    /// considered, the statistics are exactly as accurate as the distinction of
+    /// `Match` and `Subst` operations.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bio_types::alignment::{Alignment,AlignmentMode};
+    /// use bio_types::alignment::AlignmentOperation::*;
+    /// let alignment = Alignment {
+    ///     score: 5,
+    ///     xstart: 3,
+    ///     ystart: 0,
+    ///     xend: 9,
+    ///     yend: 10,
+    ///     ylen: 10,
+    ///     xlen: 10,
+    ///     operations: vec![Match, Match, Match, Subst, Ins, Ins, Del, Del, Match, Match],
+    ///     mode: AlignmentMode::Semiglobal,
+    /// };
+    /// let stats = alignment.stats();
+    /// assert_eq!(stats.matches, 5);
+    /// assert_eq!(stats.edit_distance, 5);
+    /// assert_eq!(stats.gap_opens(), 2);
+    /// assert_eq!(stats.blast_identity, 0.5);
+    /// assert_eq!(stats.gap_compressed_identity, 5.0 / 8.0);
+    /// assert_eq!(stats.query_coverage, 0.6);
+    /// ```
+    pub fn stats(&self) -> AlignmentStats {
+        let mut stats = AlignmentStats::default();
+        let mut last = None;
+        for &op in self.operations.iter() {
+            match op {
+                AlignmentOperation::Match => stats.matches += 1,
+                AlignmentOperation::Subst => stats.mismatches += 1,
+                AlignmentOperation::Ins => {
+                    stats.insertions += 1;
+                    if last != Some(AlignmentOperation::Ins) {
+                        stats.insertion_opens += 1;
+                    }
+                }
+                AlignmentOperation::Del => {
+                    stats.deletions += 1;
+                    if last != Some(AlignmentOperation::Del) {
+                        stats.deletion_opens += 1;
+                    }
+                }
+                AlignmentOperation::RefSkip => stats.skipped += 1,
+                AlignmentOperation::Xclip(_) | AlignmentOperation::Yclip(_) => {}
+            }
+            last = Some(op);
+        }
+
+        stats.edit_distance = stats.mismatches + stats.insertions + stats.deletions;
+        stats.blast_identity = fraction(stats.matches, stats.matches + stats.edit_distance);
+        stats.gap_compressed_identity = fraction(
+            stats.matches,
+            stats.matches + stats.mismatches + stats.gap_opens(),
+        );
+        stats.query_coverage = fraction(self.x_aln_len(), self.xlen);
+        stats.reference_coverage = fraction(self.y_aln_len() - stats.skipped, self.ylen);
+        stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alignment::AlignmentMode;
+    use crate::alignment::AlignmentOperation::*;
+
+    #[test]
+    fn test_stats() {
+        let alignment = Alignment {
+            score: 5,
+            xstart: 0,
+            ystart: 5,
+            xend: 4,
+            yend: 10,
+            ylen: 10,
+            xlen: 5,
+            operations: vec![Yclip(5), Match, Subst, Subst, Ins, Del, Del, Xclip(1)],
+            mode: AlignmentMode::Custom,
+        };
+        let stats = alignment.stats();
+        assert_eq!(stats.matches, 1);
+        assert_eq!(stats.mismatches, 2);
+        assert_eq!(stats.insertions, 1);
+        assert_eq!(stats.deletions, 2);
+        assert_eq!(stats.insertion_opens, 1);
+        assert_eq!(stats.deletion_opens, 1);
+        assert_eq!(stats.edit_distance, 5);
+        assert_eq!(stats.blast_identity, 1.0 / 6.0);
+        assert_eq!(stats.gap_compressed_identity, 0.2);
+        assert_eq!(stats.query_coverage, 0.8);
+        assert_eq!(stats.reference_coverage, 0.5);
+
+        assert_eq!(Alignment::default().stats(), AlignmentStats::default());
+    }
+}
@@ -0,0 +1,434 @@
+//! Validation of the invariants of an alignment, and a builder that only yields
+//! valid alignments.
+
+use thiserror::Error;
+
+use super::{Alignment, AlignmentMode, AlignmentOperation};
+
+// Lengths consumed in x and y by the leading clips, the aligned operations and the
+// trailing clips of a list of operations.
+#[derive(Default)]
+struct Extents {
+    leading: (usize, usize),
+    aligned: (usize, usize),
+    trailing: (usize, usize),
+    inner_clip: bool,
+}
+
+impl Extents {
+    fn new(operations: &[AlignmentOperation]) -> Self {
+        let mut extents = Extents::default();
+        let first = operations
+            .iter()
+            .position(|op| !is_clip(op))
+            .unwrap_or(operations.len());
+        let last = operations
+            .iter()
+            .rposition(|op| !is_clip(op))
+            .map_or(first, |i| i + 1);
+        for (i, op) in operations.iter().enumerate() {
+            let counts = if i < first {
+                &mut extents.leading
+            } else if i < last {
+                &mut extents.aligned
+            } else {
+                &mut extents.trailing
+            };
+            match *op {
+                AlignmentOperation::Match | AlignmentOperation::Subst => {
+                    counts.0 += 1;
+                    counts.1 += 1;
+                }
+                AlignmentOperation::Ins => counts.0 += 1,
+                AlignmentOperation::Del | AlignmentOperation::RefSkip => counts.1 += 1,
+                AlignmentOperation::Xclip(len) => counts.0 += len,
+                AlignmentOperation::Yclip(len) => counts.1 += len,
+            }
+            if (first..last).contains(&i) && is_clip(op) {
+                extents.inner_clip = true;
+            }
+        }
+        extents
+    }
+}
+
+pub(super) fn is_clip(op: &AlignmentOperation) -> bool {
+    matches!(
+        op,
+        AlignmentOperation::Xclip(_) | AlignmentOperation::Yclip(_)
+    )
+}
+
+impl Alignment {
+    /// Check the structural invariants of the alignment, i.e. that
+    ///
+    /// * the aligned ranges lie within the sequences,
+    /// * the operations add up to the aligned ranges,
+    /// * `Global` alignments cover both sequences and `Semiglobal` alignments cover x,
+    /// * standard mode alignments contain no clip operations, and
+    /// * `Custom` mode alignments have clip operations only at their ends, which add
+    ///   up to the unaligned prefixes and suffixes of both sequences.
+    ///
+    /// Alignments that pass are safe to use with e.g. [`path`](#method.path) and
+    /// [`pretty`](#method.pretty).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bio_types::alignment::{Alignment,AlignmentError,AlignmentMode};
+    /// use bio_types::alignment::AlignmentOperation::*;
+    /// let mut alignment = Alignment {
+    ///     score: 5,
+    ///     xstart: 3,
+    ///     ystart: 0,
+    ///     xend: 9,
+    ///     yend: 10,
+    ///     ylen: 10,
+    ///     xlen: 10,
+    ///     operations: vec![Match, Match, Match, Subst, Ins, Ins, Del, Del],
+    ///     mode: AlignmentMode::Local,
+    /// };
+    /// assert_eq!(
+    ///     alignment.validate(),
+    ///     Err(AlignmentError::ReferenceLengthMismatch { ops: 6, range: 10 })
+    /// );
+    /// alignment.ystart = 4;
+    /// assert_eq!(alignment.validate(), Ok(()));
+    /// ```
+    pub fn validate(&self) -> Result<(), AlignmentError> {
+        if self.xstart > self.xend || self.xend > self.xlen {
+            return Err(AlignmentError::InvalidQueryRange {
+                start: self.xstart,
+                end: self.xend,
+                len: self.xlen,
+            });
+        }
+        if self.ystart > self.yend || self.yend > self.ylen {
+            return Err(AlignmentError::InvalidReferenceRange {
+                start: self.ystart,
+                end: self.yend,
+                len: self.ylen,
+            });
+        }
+
+        let extents = Extents::new(&self.operations);
+        if self.mode == AlignmentMode::Custom && extents.inner_clip {
+            return Err(AlignmentError::InnerClip);
+        }
+        if self.mode != AlignmentMode::Custom && self.operations.iter().any(is_clip) {
+            return Err(AlignmentError::UnexpectedClip(self.mode));
+        }
+        if extents.aligned.0 != self.x_aln_len() {
+            return Err(AlignmentError::QueryLengthMismatch {
+                ops: extents.aligned.0,
+                range: self.x_aln_len(),
+            });
+        }
+        if extents.aligned.1 != self.y_aln_len() {
+            return Err(AlignmentError::ReferenceLengthMismatch {
+                ops: extents.aligned.1,
+                range: self.y_aln_len(),
+            });
+        }
+
+        match self.mode {
+            AlignmentMode::Global | AlignmentMode::Semiglobal
+                if self.xstart > 0 || self.xend < self.xlen =>
+            {
+                Err(AlignmentError::IncompleteQuery(self.mode))
+            }
+            AlignmentMode::Global if self.ystart > 0 || self.yend < self.ylen => {
+                Err(AlignmentError::IncompleteReference(self.mode))
+            }
+            AlignmentMode::Custom => {
+                // without aligned operations, prefix and suffix clips cannot be told apart
+                let empty = extents.aligned == (0, 0);
+                let fits = |prefix: usize, suffix: usize, start: usize, rest: usize| {
+                    if empty {
+                        prefix + suffix == start + rest
+                    } else {
+                        prefix == start && suffix == rest
+                    }
+                };
+                if !fits(
+                    extents.leading.0,
+                    extents.trailing.0,
+                    self.xstart,
+                    self.xlen - self.xend,
+                ) {
+                    Err(AlignmentError::QueryClipMismatch {
+                        prefix: extents.leading.0,
+                        suffix: extents.trailing.0,
+                    })
+                } else if !fits(
+                    extents.leading.1,
+                    extents.trailing.1,
+                    self.ystart,
+                    self.ylen - self.yend,
+                ) {
+                    Err(AlignmentError::ReferenceClipMismatch {
+                        prefix: extents.leading.1,
+                        suffix: extents.trailing.1,
+                    })
+                } else {
+                    Ok(())
+                }
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// A builder for alignments that validates the result. Positions and lengths that
+/// are not set explicitly are derived from the operations: in `Custom` mode, the
+/// start positions are given by the leading clips and the sequence lengths include
+/// the trailing clips; in the standard modes, the start positions default to zero
+/// and the sequence lengths to the end positions.
+///
+/// # Example
+///
+/// ```
+/// use bio_types::alignment::{AlignmentBuilder,AlignmentError,AlignmentMode};
+/// use bio_types::alignment::AlignmentOperation::*;
+/// let alignment = AlignmentBuilder::new(vec![Xclip(2), Match, Subst, Del, Yclip(3)])
+///     .mode(AlignmentMode::Custom)
+///     .score(-1)
+///     .build()
+///     .unwrap();
+/// assert_eq!((alignment.xstart, alignment.xend, alignment.xlen), (2, 4, 4));
+/// assert_eq!((alignment.ystart, alignment.yend, alignment.ylen), (0, 3, 6));
+///
+/// let alignment = AlignmentBuilder::new(vec![Match, Subst, Ins])
+///     .xlen(5)
+///     .mode(AlignmentMode::Semiglobal)
+///     .build();
+/// assert_eq!(alignment, Err(AlignmentError::IncompleteQuery(AlignmentMode::Semiglobal)));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct AlignmentBuilder {
+    operations: Vec<AlignmentOperation>,
+    mode: AlignmentMode,
+    score: i32,
+    xstart: Option<usize>,
+    ystart: Option<usize>,
+    xend: Option<usize>,
+    yend: Option<usize>,
+    xlen: Option<usize>,
+    ylen: Option<usize>,
+}
+
+impl AlignmentBuilder {
+    /// Start building an alignment with the given operations. By default, the
+    /// alignment is in `Global` mode and has a score of zero.
+    pub fn new(operations: Vec<AlignmentOperation>) -> Self {
+        AlignmentBuilder {
+            operations,
+            ..Default::default()
+        }
+    }
+
+    /// Set the alignment mode.
+    pub fn mode(mut self, mode: AlignmentMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Set the alignment score.
+    pub fn score(mut self, score: i32) -> Self {
+        self.score = score;
+        self
+    }
+
+    /// Set the start on x, by default after the leading clips in `Custom` mode or 0.
+    pub fn xstart(mut self, xstart: usize) -> Self {
+        self.xstart = Some(xstart);
+        self
+    }
+
+    /// Set the start on y, by default after the leading clips in `Custom` mode or 0.
+    pub fn ystart(mut self, ystart: usize) -> Self {
+        self.ystart = Some(ystart);
+        self
+    }
+
+    /// Set the end on x, by default the start plus the aligned length of x.
+    pub fn xend(mut self, xend: usize) -> Self {
+        self.xend = Some(xend);
+        self
+    }
+
+    /// Set the end on y, by default the start plus the aligned length of y.
+    pub fn yend(mut self, yend: usize) -> Self {
+        self.yend = Some(yend);
+        self
+    }
+
+    /// Set the length of x, by default the end plus any trailing clips in `Custom` mode.
+    pub fn xlen(mut self, xlen: usize) -> Self {
+        self.xlen = Some(xlen);
+        self
+    }
+
+    /// Set the length of y, by default the end plus any trailing clips in `Custom` mode.
+    pub fn ylen(mut self, ylen: usize) -> Self {
+        self.ylen = Some(ylen);
+        self
+    }
+
+    /// Build the alignment, returning an error if it is invalid (see
+    /// [`Alignment::validate`](struct.Alignment.html#method.validate)).
+    pub fn build(self) -> Result<Alignment, AlignmentError> {
+        let extents = Extents::new(&self.operations);
+        let custom = self.mode == AlignmentMode::Custom;
+        let xstart = self
+            .xstart
+            .unwrap_or(if custom { extents.leading.0 } else { 0 });
+        let ystart = self
+            .ystart
+            .unwrap_or(if custom { extents.leading.1 } else { 0 });
+        let xend = self.xend.unwrap_or(xstart + extents.aligned.0);
+        let yend = self.yend.unwrap_or(ystart + extents.aligned.1);
+        let xlen = self.xlen.unwrap_or(if custom {
+            xend + extents.trailing.0
+        } else {
+            xend
+        });
+        let ylen = self.ylen.unwrap_or(if custom {
+            yend + extents.trailing.1
+        } else {
+            yend
+        });
+
+        let alignment = Alignment {
+            score: self.score,
+            ystart,
+            xstart,
+            yend,
+            xend,
+            ylen,
+            xlen,
+            operations: self.operations,
+            mode: self.mode,
+        };
+        alignment.validate()?;
+        Ok(alignment)
+    }
+}
+
+/// Violations of the invariants of an alignment.
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum AlignmentError {
+    #[error("invalid aligned range {start}..{end} of query sequence of length {len}")]
+    InvalidQueryRange {
+        start: usize,
+        end: usize,
+        len: usize,
+    },
+    #[error("invalid aligned range {start}..{end} of reference sequence of length {len}")]
+    InvalidReferenceRange {
+        start: usize,
+        end: usize,
+        len: usize,
+    },
+    #[error("operations consume {ops} query bases, but the aligned range has length {range}")]
+    QueryLengthMismatch { ops: usize, range: usize },
+    #[error("operations consume {ops} reference bases, but the aligned range has length {range}")]
+    ReferenceLengthMismatch { ops: usize, range: usize },
+    #[error("{0:?} alignment does not cover the whole query sequence")]
+    IncompleteQuery(AlignmentMode),
+    #[error("{0:?} alignment does not cover the whole reference sequence")]
+    IncompleteReference(AlignmentMode),
+    #[error("clip operations are not allowed in {0:?} mode")]
+    UnexpectedClip(AlignmentMode),
+    #[error("clip operation between aligned operations")]
+    InnerClip,
+    #[error("query clips of {prefix} and {suffix} bases do not match the aligned range")]
+    QueryClipMismatch { prefix: usize, suffix: usize },
+    #[error("reference clips of {prefix} and {suffix} bases do not match the aligned range")]
+    ReferenceClipMismatch { prefix: usize, suffix: usize },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alignment::AlignmentOperation::*;
+
+    #[test]
+    fn test_validate() {
+        let alignment = Alignment::from_cigar("3S3=1X2I2D1S", 5, 10, 20).unwrap();
+        assert_eq!(alignment.validate(), Ok(()));
+
+        let mut invalid = alignment.clone();
+        invalid.operations.swap(1, 2);
+        assert_eq!(invalid.validate(), Err(AlignmentError::InnerClip));
+
+        let mut invalid = alignment.clone();
+        invalid.operations[11] = Yclip(8);
+        assert_eq!(
+            invalid.validate(),
+            Err(AlignmentError::ReferenceClipMismatch {
+                prefix: 5,
+                suffix: 8
+            })
+        );
+
+        let mut invalid = alignment.clone();
+        invalid.xend = 11;
+        assert_eq!(
+            invalid.validate(),
+            Err(AlignmentError::InvalidQueryRange {
+                start: 3,
+                end: 11,
+                len: 10
+            })
+        );
+
+        let mut invalid = alignment;
+        invalid.mode = AlignmentMode::Local;
+        assert_eq!(
+            invalid.validate(),
+            Err(AlignmentError::UnexpectedClip(AlignmentMode::Local))
+        );
+        invalid.filter_clip_operations();
+        assert_eq!(invalid.validate(), Ok(()));
+        invalid.mode = AlignmentMode::Global;
+        assert_eq!(
+            invalid.validate(),
+            Err(AlignmentError::IncompleteQuery(AlignmentMode::Global))
+        );
+    }
+
+    #[test]
+    fn test_builder() {
+        let alignment = AlignmentBuilder::new(vec![Match, Ins, Del, Match])
+            .build()
+            .unwrap();
+        assert_eq!((alignment.xlen, alignment.ylen), (3, 3));
+        assert_eq!(alignment.path().last(), Some(&(3, 3, Match)));
+
+        let alignment = AlignmentBuilder::new(vec![Yclip(4), Xclip(3)])
+            .mode(AlignmentMode::Custom)
+            .build()
+            .unwrap();
+        assert_eq!((alignment.xstart, alignment.xlen), (3, 3));
+        assert_eq!((alignment.ystart, alignment.ylen), (4, 4));
+
+        assert_eq!(
+            AlignmentBuilder::new(vec![Match, Del])
+                .xstart(1)
+                .ystart(1)
+                .ylen(4)
+                .mode(AlignmentMode::Global)
+                .build(),
+            Err(AlignmentError::IncompleteQuery(AlignmentMode::Global))
+        );
+        assert_eq!(
+            AlignmentBuilder::new(vec![Match, Del])
+                .ylen(4)
+                .mode(AlignmentMode::Semiglobal)
+                .build()
+                .map(|alignment| alignment.yend),
+            Ok(2)
+        );
+    }
+}
@@ -0,0 +1,220 @@
+//! Calling of variants from a pairwise alignment of a query (e.g. an assembly)
+//! against a reference.
+
+use super::{Alignment, AlignmentMode, AlignmentOperation, TextSlice};
+use crate::genome::{Locus, Position};
+use crate::variant::{Kind, Variant};
+
+impl Alignment {
+    /// Iterate over the differences between the query sequence x and the reference
+    /// sequence y as variants, given by their reference position and kind:
+    ///
+    /// * aligned bases that differ (case-insensitively) become SNVs with the query
+    ///   base, irrespective of whether they are marked as `Match` or `Subst`,
+    /// * runs of insertions become a single `Insertion` of the query bases, located
+    ///   at the reference position they are inserted before, and
+    /// * runs of deletions become a single `Deletion`, located at the first deleted
+    ///   reference position.
+    ///
+    /// Adjacent SNVs can be merged into MNVs with
+    /// [`merge_mnvs`](variants/struct.Variants.html#method.merge_mnvs).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bio_types::alignment::{Alignment,AlignmentMode};
+    /// use bio_types::alignment::AlignmentOperation::*;
+    /// use bio_types::genome::AbstractLocus;
+    /// use bio_types::variant::{AbstractVariant, Kind};
+    /// let x = b"ACGTTTACGA";
+    /// let y = b"ACGATACCCGA";
+    /// let alignment = Alignment {
+    ///     score: 0,
+    ///     xstart: 0,
+    ///     ystart: 0,
+    ///     xend: 10,
+    ///     yend: 11,
+    ///     ylen: 11,
+    ///     xlen: 10,
+    ///     operations: vec![
+    ///         Match, Match, Match, Subst, Ins, Match, Match, Match, Del, Del, Match, Match,
+    ///     ],
+    ///     mode: AlignmentMode::Global,
+    /// };
+    /// let variants: Vec<_> = alignment.variants(x, y).collect();
+    /// assert_eq!(
+    ///     variants,
+    ///     [
+    ///         (3, Kind::SNV(b'T')),
+    ///         (4, Kind::Insertion(b"T".to_vec())),
+    ///         (7, Kind::Deletion(2))
+    ///     ]
+    /// );
+    ///
+    /// let variants: Vec<_> = alignment.variants(x, y).on_contig("chr1").collect();
+    /// assert_eq!(variants[2].contig(), "chr1");
+    /// assert_eq!(variants[2].pos(), 7);
+    /// assert_eq!(variants[2].kind(), &Kind::Deletion(2));
+    /// ```
+    pub fn variants<'a>(&'a self, x: TextSlice<'a>, y: TextSlice<'a>) -> Variants<'a> {
+        let (x_i, y_i) = match self.mode {
+            AlignmentMode::Custom => (0, 0),
+            _ => (self.xstart, self.ystart),
+        };
+        Variants {
+            operations: &self.operations,
+            x,
+            y,
+            x_i,
+            y_i,
+            merge_mnvs: false,
+        }
+    }
+}
+
+/// Iterator over the variants of an alignment, see
+/// [`Alignment::variants`](../struct.Alignment.html#method.variants).
+#[derive(Debug, Clone)]
+pub struct Variants<'a> {
+    operations: &'a [AlignmentOperation],
+    x: TextSlice<'a>,
+    y: TextSlice<'a>,
+    x_i: usize,
+    y_i: usize,
+    merge_mnvs: bool,
+}
+
+impl<'a> Variants<'a> {
+    /// Merge runs of adjacent SNVs into MNVs.
+    pub fn merge_mnvs(mut self, merge: bool) -> Self {
+        self.merge_mnvs = merge;
+        self
+    }
+
+    /// Anchor the variants on the given contig, which has to be the reference
+    /// sequence y.
+    pub fn on_contig(self, contig: &str) -> impl Iterator<Item = Variant> + 'a {
+        let contig = contig.to_owned();
+        self.map(move |(pos, kind)| Variant::new(Locus::new(contig.clone(), pos as Position), kind))
+    }
+
+    // Whether the current operation is the given one.
+    fn peek(&self, op: AlignmentOperation) -> bool {
+        self.operations.first() == Some(&op)
+    }
+
+    // Whether the current operation aligns differing bases.
+    fn peek_mismatch(&self) -> bool {
+        matches!(
+            self.operations.first(),
+            Some(AlignmentOperation::Match | AlignmentOperation::Subst)
+        ) && !self.x[self.x_i].eq_ignore_ascii_case(&self.y[self.y_i])
+    }
+
+    // Move to the next operation.
+    fn advance(&mut self) {
+        match self.operations[0] {
+            AlignmentOperation::Match | AlignmentOperation::Subst => {
+                self.x_i += 1;
+                self.y_i += 1;
+            }
+            AlignmentOperation::Ins => self.x_i += 1,
+            AlignmentOperation::Del | AlignmentOperation::RefSkip => self.y_i += 1,
+            AlignmentOperation::Xclip(len) => self.x_i += len,
+            AlignmentOperation::Yclip(len) => self.y_i += len,
+        }
+        self.operations = &self.operations[1..];
+    }
+}
+
+impl Iterator for Variants<'_> {
+    type Item = (usize, Kind);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(&op) = self.operations.first() {
+            let pos = self.y_i;
+            match op {
+                AlignmentOperation::Match | AlignmentOperation::Subst if self.peek_mismatch() => {
+                    let mut bases = vec![self.x[self.x_i]];
+                    self.advance();
+                    while self.merge_mnvs && self.peek_mismatch() {
+                        bases.push(self.x[self.x_i]);
+                        self.advance();
+                    }
+                    let kind = if bases.len() == 1 {
+                        Kind::SNV(bases[0])
+                    } else {
+                        Kind::MNV(bases)
+                    };
+                    return Some((pos, kind));
+                }
+                AlignmentOperation::Ins => {
+                    let mut bases = Vec::new();
+                    while self.peek(AlignmentOperation::Ins) {
+                        bases.push(self.x[self.x_i]);
+                        self.advance();
+                    }
+                    return Some((pos, Kind::Insertion(bases)));
+                }
+                AlignmentOperation::Del => {
+                    while self.peek(AlignmentOperation::Del) {
+                        self.advance();
+                    }
+                    return Some((pos, Kind::Deletion((self.y_i - pos) as u64)));
+                }
+                _ => self.advance(),
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alignment::AlignmentOperation::*;
+
+    #[test]
+    fn test_variants() {
+        let x = b"GGACGTTCGT";
+        let y = b"TTTACCAAGGTA";
+        let alignment = Alignment {
+            score: 0,
+            xstart: 2,
+            ystart: 3,
+            xend: 10,
+            yend: 11,
+            ylen: 12,
+            xlen: 10,
+            operations: vec![
+                Yclip(3),
+                Xclip(2),
+                Match,
+                Match,
+                Subst,
+                Subst,
+                Del,
+                Subst,
+                Ins,
+                Match,
+                Match,
+                Yclip(1),
+            ],
+            mode: AlignmentMode::Custom,
+        };
+        let variants: Vec<_> = alignment.variants(x, y).collect();
+        assert_eq!(
+            variants,
+            [
+                (5, Kind::SNV(b'G')),
+                (6, Kind::SNV(b'T')),
+                (7, Kind::Deletion(1)),
+                (8, Kind::SNV(b'T')),
+                (9, Kind::Insertion(b"C".to_vec())),
+            ]
+        );
+        let variants: Vec<_> = alignment.variants(x, y).merge_mnvs(true).collect();
+        assert_eq!(variants[0], (5, Kind::MNV(b"GT".to_vec())));
+        assert_eq!(variants[2], (8, Kind::SNV(b'T')));
+    }
+}
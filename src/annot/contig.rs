@@ -17,6 +17,7 @@ use regex::Regex;
 use crate::annot::loc::Loc;
 use crate::annot::pos::Pos;
 use crate::annot::*;
+use crate::genome::{CoordError, Pos0, Pos1};
 use crate::strand::*;
 
 /// Contiguous sequence region on a particular, named sequence (e.g. a
@@ -70,6 +71,33 @@ impl<R, S> Contig<R, S> {
         }
     }
 
+    /// Construct a new sequence contig location from 0-based, half-open
+    /// coordinates, as in BED records. The location is empty if `end`
+    /// precedes `start`. Fails if a coordinate exceeds `isize::MAX`.
+    pub fn from_half_open(refid: R, start: Pos0, end: Pos0, strand: S) -> Result<Self, CoordError> {
+        let (start, end) = (super::annot_pos(start)?, super::annot_pos(end)?);
+        let length = end.saturating_sub(start).max(0) as usize;
+        Ok(Contig::new(refid, start, length, strand))
+    }
+
+    /// Construct a new sequence contig location from 1-based, closed
+    /// coordinates, as in GFF records. The location is empty if `end`
+    /// precedes `start`. Fails if a coordinate exceeds `isize::MAX`.
+    ///
+    /// ```
+    /// use bio_types::annot::contig::Contig;
+    /// use bio_types::genome::Pos1;
+    /// use bio_types::strand::ReqStrand;
+    /// let (start, end) = (Pos1::new(461830).unwrap(), Pos1::new(462426).unwrap());
+    /// let tma22 = Contig::from_closed("chrX".to_owned(), start, end, ReqStrand::Forward)
+    ///     .unwrap();
+    /// assert_eq!(tma22.to_string(), "chrX:461829-462426(+)");
+    /// ```
+    pub fn from_closed(refid: R, start: Pos1, end: Pos1, strand: S) -> Result<Self, CoordError> {
+        // a closed 1-based end equals the half-open 0-based end
+        Contig::from_half_open(refid, start.into(), Pos0::new(end.get()), strand)
+    }
+
     /// Construct a new sequence contig location from a starting
     /// position and length.
     ///
@@ -143,9 +171,9 @@ impl<R> Contig<R, ReqStrand> {
     /// # Arguments
     ///
     /// * `dist` specifies the offset for sliding the position. The
-    /// left, 5'-most end of the contig will expand for forward-strand
-    /// annotations and the right, 3'-most end will expand for
-    /// reverse-strand annotations.
+    ///   left, 5'-most end of the contig will expand for forward-strand
+    ///   annotations and the right, 3'-most end will expand for
+    ///   reverse-strand annotations.
     ///
     /// ```
     /// use bio_types::annot::contig::Contig;
@@ -170,9 +198,9 @@ impl<R> Contig<R, ReqStrand> {
     /// # Arguments
     ///
     /// * `dist` specifies the offset for sliding the position. The
-    /// right, 3'-most end of the contig will expand for
-    /// forward-strand annotations and the left, 5'-most end will
-    /// expand for reverse-strand annotations.
+    ///   right, 3'-most end of the contig will expand for
+    ///   forward-strand annotations and the left, 5'-most end will
+    ///   expand for reverse-strand annotations.
     ///
     /// ```
     /// use bio_types::annot::contig::Contig;
@@ -319,8 +347,8 @@ where
 
         let cap = CONTIG_RE.captures(s).ok_or(ParseAnnotError::BadAnnot)?;
 
-        let start = cap[2].parse::<isize>().map_err(ParseAnnotError::ParseInt)?;
-        let end = cap[3].parse::<isize>().map_err(ParseAnnotError::ParseInt)?;
+        let start = super::parse_pos0(&cap[2])?;
+        let end = super::parse_pos0(&cap[3])?;
         let strand = cap
             .get(4)
             .map_or("", |m| m.as_str())
@@ -328,12 +356,12 @@ where
             .map_err(ParseAnnotError::ParseStrand)?;
 
         if start <= end {
-            Ok(Contig::new(
+            Ok(Contig::from_half_open(
                 R::from(cap[1].to_owned()),
                 start,
-                (end - start) as usize,
+                end,
                 strand,
-            ))
+            )?)
         } else {
             Err(ParseAnnotError::EndBeforeStart)
         }
@@ -462,7 +490,7 @@ mod tests {
         assert!(None.same(&p0_into));
     }
 
-    fn test_contig_ixn(ca_str: &str, cb_str: &str, cab_str: Option<String>) -> () {
+    fn test_contig_ixn(ca_str: &str, cb_str: &str, cab_str: Option<String>) {
         let ca = ca_str.parse::<SeqContigStranded>().unwrap();
         let cb = cb_str.parse::<SeqContigStranded>().unwrap();
         match ca.contig_intersection(&cb) {
@@ -482,6 +510,34 @@ mod tests {
         assert_eq!(format!("{}", tma19), "chrXI:334412-334916(-)");
     }
 
+    #[test]
+    fn from_coordinates() {
+        let bed = Contig::from_half_open(
+            "chrXI".to_owned(),
+            Pos0::new(334412),
+            Pos0::new(334916),
+            ReqStrand::Reverse,
+        )
+        .unwrap();
+        let gff = Contig::from_closed(
+            "chrXI".to_owned(),
+            Pos1::new(334413).unwrap(),
+            Pos1::new(334916).unwrap(),
+            ReqStrand::Reverse,
+        )
+        .unwrap();
+        assert_eq!(bed, gff);
+        let huge = Pos0::new(isize::MAX as u64 + 1);
+        assert_eq!(
+            Contig::from_half_open("chrXI".to_owned(), Pos0::new(0), huge, NoStrand::Unknown),
+            Err(CoordError::OutOfRange)
+        );
+        assert!(matches!(
+            "chrXI:0-9223372036854775808".parse::<Contig<String, NoStrand>>(),
+            Err(ParseAnnotError::Coord(CoordError::OutOfRange))
+        ));
+    }
+
     #[test]
     fn intersection() {
         test_contig_ixn(
@@ -44,6 +44,9 @@
 //! # fn main() { try_main().unwrap(); }
 //! ```
 
+use std::convert::TryFrom;
+
+use crate::genome::{CoordError, Pos0};
 use crate::strand;
 use thiserror::Error;
 
@@ -66,6 +69,8 @@ pub enum ParseAnnotError {
     Splicing(#[from] spliced::SplicingError),
     #[error("Ending position < starting position")]
     EndBeforeStart,
+    #[error("Position out of range")]
+    Coord(#[from] CoordError),
 }
 
 // Errors that arise in maniuplating annotations
@@ -76,3 +81,13 @@ pub enum AnnotError {
     #[error("Invalid splicing structure")]
     BadSplicing,
 }
+
+// Parse a position of an annotation string, which is 0-based.
+fn parse_pos0(s: &str) -> Result<Pos0, ParseAnnotError> {
+    Ok(Pos0::new(s.parse()?))
+}
+
+// Convert a 0-based position into an annotation coordinate, which is an `isize`.
+fn annot_pos(pos: Pos0) -> Result<isize, CoordError> {
+    isize::try_from(pos.get()).map_err(|_| CoordError::OutOfRange)
+}
@@ -17,6 +17,7 @@ use regex::Regex;
 use crate::annot::contig::Contig;
 use crate::annot::loc::Loc;
 use crate::annot::*;
+use crate::genome::{CoordError, Pos0, Pos1};
 use crate::strand::*;
 
 /// Position on a particular, named sequence (e.g. a chromosome).
@@ -63,6 +64,27 @@ impl<R, S> Pos<R, S> {
         Pos { refid, pos, strand }
     }
 
+    /// Construct a new sequence position from a 0-based position, as in BED or
+    /// BAM records. Fails if the position exceeds `isize::MAX`.
+    pub fn from_pos0(refid: R, pos: Pos0, strand: S) -> Result<Self, CoordError> {
+        Ok(Pos::new(refid, super::annot_pos(pos)?, strand))
+    }
+
+    /// Construct a new sequence position from a 1-based position, as in GFF, SAM
+    /// or VCF records. Fails if the position exceeds `isize::MAX`.
+    ///
+    /// ```
+    /// use bio_types::annot::pos::Pos;
+    /// use bio_types::genome::Pos1;
+    /// use bio_types::strand::NoStrand;
+    /// let snp = Pos::from_pos1("chr1".to_owned(), Pos1::new(10177).unwrap(), NoStrand::Unknown)
+    ///     .unwrap();
+    /// assert_eq!(snp.pos(), 10176);
+    /// ```
+    pub fn from_pos1(refid: R, pos: Pos1, strand: S) -> Result<Self, CoordError> {
+        Pos::from_pos0(refid, pos.into(), strand)
+    }
+
     /// Position on the reference sequence (0-based).
     pub fn pos(&self) -> isize {
         self.pos
@@ -89,9 +111,9 @@ where
     /// # Arguments
     ///
     /// * `dist` specifies the offset for sliding the position. A
-    /// positive `dist` will numerically increase the position for
-    /// forward-strand features and decrease it for reverse-strand
-    /// features.
+    ///   positive `dist` will numerically increase the position for
+    ///   forward-strand features and decrease it for reverse-strand
+    ///   features.
     ///
     /// ```
     /// use bio_types::annot::pos::Pos;
@@ -120,9 +142,9 @@ where
     /// # Arguments
     ///
     /// * `dist` specifies the offset for sliding the position. A
-    /// positive `dist` will numerically decrease the position for
-    /// forward-strand features and increase it for reverse-strand
-    /// features.
+    ///   positive `dist` will numerically decrease the position for
+    ///   forward-strand features and increase it for reverse-strand
+    ///   features.
     ///
     /// ```
     /// use bio_types::annot::pos::Pos;
@@ -259,11 +281,11 @@ where
             .parse::<S>()
             .map_err(ParseAnnotError::ParseStrand)?;
 
-        Ok(Pos::new(
+        Ok(Pos::from_pos0(
             R::from(cap[1].to_owned()),
-            cap[2].parse::<isize>().map_err(ParseAnnotError::ParseInt)?,
+            super::parse_pos0(&cap[2])?,
             strand,
-        ))
+        )?)
     }
 }
 
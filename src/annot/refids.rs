@@ -67,8 +67,8 @@ impl<R> RefIDSet<R> {
         R: Deref<Target = String> + From<String> + Clone,
     {
         if self.refids.contains_key(id) {
-            if let Some(ref r) = self.refids.get(id) {
-                (*r).clone()
+            if let Some(r) = self.refids.get(id) {
+                r.clone()
             } else {
                 panic!("RefIDSet::ensure failed to get() after contains()");
             }
@@ -308,7 +308,7 @@ impl<R, S> Spliced<R, S> {
         self.exon_0_length == 0 && self.inexes.is_empty()
     }
 
-    fn exes(&self) -> inex::Exes {
+    fn exes(&self) -> inex::Exes<'_> {
         inex::Exes::new(self.exon_0_length, &self.inexes)
     }
 
@@ -589,8 +589,11 @@ where
         let mut starts = Vec::new();
         let mut lengths = Vec::new();
 
-        let first_start = cap[2].parse::<isize>().map_err(ParseAnnotError::ParseInt)?;
-        let first_end = cap[3].parse::<isize>().map_err(ParseAnnotError::ParseInt)?;
+        let pos = |s: &str| -> Result<isize, ParseAnnotError> {
+            Ok(super::annot_pos(super::parse_pos0(s)?)?)
+        };
+        let first_start = pos(&cap[2])?;
+        let first_end = pos(&cap[3])?;
         let strand = cap[5].parse::<S>().map_err(ParseAnnotError::ParseStrand)?;
 
         starts.push(0);
@@ -599,12 +602,8 @@ where
         let exon_caps = EXON_RE.captures_iter(&cap[4]);
 
         for exon_cap in exon_caps {
-            let next_start = exon_cap[1]
-                .parse::<isize>()
-                .map_err(ParseAnnotError::ParseInt)?;
-            let next_end = exon_cap[2]
-                .parse::<isize>()
-                .map_err(ParseAnnotError::ParseInt)?;
+            let next_start = pos(&exon_cap[1])?;
+            let next_end = pos(&exon_cap[2])?;
             starts.push((next_start - first_start) as usize);
             lengths.push((next_end - next_start) as usize);
         }
@@ -706,8 +705,8 @@ mod tests {
         let tma20 = Spliced::with_lengths_starts(
             "chrV".to_owned(),
             166236,
-            &vec![535, 11],
-            &vec![0, 638],
+            &[535, 11],
+            &[0, 638],
             ReqStrand::Reverse,
         )
         .unwrap();
@@ -730,8 +729,8 @@ mod tests {
         let rpl7b = Spliced::with_lengths_starts(
             "chrXVI".to_owned(),
             173151,
-            &vec![11, 94, 630],
-            &vec![0, 420, 921],
+            &[11, 94, 630],
+            &[0, 420, 921],
             ReqStrand::Forward,
         )
         .unwrap();
@@ -756,8 +755,8 @@ mod tests {
         let tad3 = Spliced::with_lengths_starts(
             "chrXII".to_owned(),
             765265,
-            &vec![808, 52, 109],
-            &vec![0, 864, 984],
+            &[808, 52, 109],
+            &[0, 864, 984],
             ReqStrand::Reverse,
         )
         .unwrap();
@@ -783,7 +782,7 @@ mod tests {
         outstr: &str,
         in_offset: isize,
         in_strand: ReqStrand,
-    ) -> () {
+    ) {
         let p0 = outstr.parse::<Pos<String, ReqStrand>>().unwrap();
         let p0_into_expected = Pos::new((), in_offset, in_strand);
         let p0_into_actual = loc.pos_into(&p0);
@@ -796,7 +795,7 @@ mod tests {
         assert!(Some(p0).same(&p0_back_out_actual));
     }
 
-    fn test_no_into(loc: &Spliced<String, ReqStrand>, outstr: &str) -> () {
+    fn test_no_into(loc: &Spliced<String, ReqStrand>, outstr: &str) {
         let p0 = outstr.parse::<Pos<String, ReqStrand>>().unwrap();
         assert!(None.same(&loc.pos_into(&p0)));
     }
@@ -807,8 +806,8 @@ mod tests {
         let rpl7b = Spliced::with_lengths_starts(
             "chrXVI".to_owned(),
             173151,
-            &vec![11, 94, 630],
-            &vec![0, 420, 921],
+            &[11, 94, 630],
+            &[0, 420, 921],
             ReqStrand::Forward,
         )
         .unwrap();
@@ -836,8 +835,8 @@ mod tests {
         let tad3 = Spliced::with_lengths_starts(
             "chrXII".to_owned(),
             765265,
-            &vec![808, 52, 109],
-            &vec![0, 864, 984],
+            &[808, 52, 109],
+            &[0, 864, 984],
             ReqStrand::Reverse,
         )
         .unwrap();
@@ -865,11 +864,7 @@ mod tests {
         assert!(None.same(&tad3.pos_outof(&p0_into)));
     }
 
-    fn test_contig_ixn(
-        spl: &Spliced<String, ReqStrand>,
-        cb_str: &str,
-        cab_str: Option<String>,
-    ) -> () {
+    fn test_contig_ixn(spl: &Spliced<String, ReqStrand>, cb_str: &str, cab_str: Option<String>) {
         let cb = cb_str.parse::<Contig<String, ReqStrand>>().unwrap();
         match spl.contig_intersection(&cb) {
             None => assert_eq!(None, cab_str),
@@ -883,8 +878,8 @@ mod tests {
         let rpl7b = Spliced::with_lengths_starts(
             "chrXVI".to_owned(),
             173151,
-            &vec![11, 94, 630],
-            &vec![0, 420, 921],
+            &[11, 94, 630],
+            &[0, 420, 921],
             ReqStrand::Forward,
         )
         .unwrap();
@@ -0,0 +1,154 @@
+//! Positions tagged with their coordinate system, so that 0-based and 1-based
+//! coordinates cannot be mixed up by accident.
+//!
+//! Formats differ in how they count positions. BED, BAM and PAF use 0-based
+//! coordinates with half-open ranges, where the first base of a contig is 0 and
+//! `start..end` excludes `end`. SAM, VCF, GFF and samtools regions use 1-based
+//! coordinates with closed ranges, where the first base is 1 and `start-end`
+//! includes `end`. [`Pos0`](struct.Pos0.html) and [`Pos1`](struct.Pos1.html) wrap a
+//! [`Position`](../type.Position.html) in the respective system, and can only be
+//! converted into each other explicitly.
+//!
+//! # Example
+//!
+//! ```
+//! use bio_types::genome::{AbstractInterval, Interval, Pos0, Pos1};
+//! // a VCF record at POS 100, i.e. the 100th base of the contig
+//! let pos: Pos1 = "100".parse().unwrap();
+//! assert_eq!(Pos0::from(pos), Pos0::new(99));
+//!
+//! // a GFF feature from 100 to 200 and the same feature as a BED record
+//! let gff = Interval::from_closed("chr1".to_owned(), pos, Pos1::new(200).unwrap());
+//! let bed = Interval::from_half_open("chr1".to_owned(), Pos0::new(99), Pos0::new(200));
+//! assert_eq!(gff, bed);
+//! assert_eq!(gff.range(), 99..200);
+//! ```
+
+use std::convert::TryFrom;
+use std::fmt::{self, Display, Formatter};
+use std::num::ParseIntError;
+use std::str::FromStr;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::Position;
+
+/// A 0-based position, as used by BED, BAM and PAF, and by
+/// [`Interval`](../struct.Interval.html) and [`Locus`](../struct.Locus.html).
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Default, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
+#[repr(transparent)]
+pub struct Pos0(Position);
+
+impl Pos0 {
+    /// Construct a 0-based position.
+    pub fn new(pos: Position) -> Self {
+        Pos0(pos)
+    }
+
+    /// The 0-based position.
+    pub fn get(self) -> Position {
+        self.0
+    }
+}
+
+/// A 1-based position, as used by SAM, VCF, GFF and samtools regions. Position 0
+/// does not exist in this coordinate system.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
+#[repr(transparent)]
+pub struct Pos1(Position);
+
+impl Pos1 {
+    /// Construct a 1-based position, or `None` if `pos` is 0.
+    pub fn new(pos: Position) -> Option<Self> {
+        if pos > 0 {
+            Some(Pos1(pos))
+        } else {
+            None
+        }
+    }
+
+    /// The 1-based position.
+    pub fn get(self) -> Position {
+        self.0
+    }
+}
+
+impl From<Pos1> for Pos0 {
+    fn from(pos: Pos1) -> Self {
+        Pos0(pos.0 - 1)
+    }
+}
+
+/// Fails for `Position::MAX`, whose 1-based equivalent is not a `Position`.
+impl TryFrom<Pos0> for Pos1 {
+    type Error = CoordError;
+
+    fn try_from(pos: Pos0) -> Result<Self, Self::Error> {
+        pos.0.checked_add(1).map(Pos1).ok_or(CoordError::Overflow)
+    }
+}
+
+impl Display for Pos0 {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Display for Pos1 {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl FromStr for Pos0 {
+    type Err = CoordError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Pos0(s.parse()?))
+    }
+}
+
+impl FromStr for Pos1 {
+    type Err = CoordError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Pos1::new(s.parse()?).ok_or(CoordError::ZeroPosition)
+    }
+}
+
+/// Errors that arise when parsing positions.
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum CoordError {
+    #[error("invalid position")]
+    ParseInt(#[from] ParseIntError),
+    #[error("position 0 is invalid in 1-based coordinates")]
+    ZeroPosition,
+    #[error("position exceeds the largest 1-based position")]
+    Overflow,
+    #[error("position exceeds the largest annotation coordinate")]
+    OutOfRange,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conversion() {
+        let pos = Pos1::new(1).unwrap();
+        assert_eq!(Pos0::from(pos), Pos0::new(0));
+        assert_eq!(Pos1::try_from(Pos0::from(pos)), Ok(pos));
+        assert_eq!(
+            Pos1::try_from(Pos0::new(Position::MAX)),
+            Err(CoordError::Overflow)
+        );
+        assert_eq!(Pos1::new(0), None);
+        assert_eq!("0".parse::<Pos1>(), Err(CoordError::ZeroPosition));
+        assert!(matches!("-1".parse::<Pos0>(), Err(CoordError::ParseInt(_))));
+        assert_eq!("0".parse::<Pos0>().unwrap().to_string(), "0");
+    }
+}
@@ -0,0 +1,365 @@
+//! Genome dictionaries, i.e. the names, lengths and order of the contigs of a
+//! reference genome, as given by samtools `.fai`, Picard `.dict` and bedtools
+//! `.genome` files.
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::{Path, PathBuf};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::{AbstractInterval, AbstractLocus, Interval, Length, Locus, Position};
+
+/// The contigs of a reference genome with their lengths, in reference order.
+///
+/// # Example
+///
+/// ```
+/// use bio_types::genome::{GenomeDict, Interval};
+/// let fai = "chr1\t248956422\t112\t70\t71\nchr2\t242193529\t252513167\t70\t71\n";
+/// let dict = GenomeDict::read_fai(fai.as_bytes()).unwrap();
+/// assert_eq!(dict.contig_len("chr2"), Some(242193529));
+/// assert_eq!(dict.contig_index("chr2"), Some(1));
+///
+/// let interval = Interval::new("chr1".to_owned(), 248956000..248957000);
+/// assert!(dict.check_interval(&interval).is_err());
+/// assert_eq!(
+///     dict.clamp_interval(&interval).unwrap(),
+///     Interval::new("chr1".to_owned(), 248956000..248956422)
+/// );
+/// ```
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(try_from = "Vec<(String, Length)>", into = "Vec<(String, Length)>")
+)]
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct GenomeDict {
+    contigs: Vec<(String, Length)>,
+    ids: HashMap<String, usize>,
+}
+
+impl GenomeDict {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append a contig.
+    pub fn push(&mut self, contig: &str, len: Length) -> Result<(), GenomeDictError> {
+        if self.ids.contains_key(contig) {
+            return Err(GenomeDictError::DuplicateContig(contig.to_owned()));
+        }
+        self.ids.insert(contig.to_owned(), self.contigs.len());
+        self.contigs.push((contig.to_owned(), len));
+        Ok(())
+    }
+
+    /// Read a samtools FASTA index (`.fai`), using its first two columns.
+    pub fn read_fai<R: BufRead>(reader: R) -> Result<Self, GenomeDictError> {
+        Self::read_columns(reader)
+    }
+
+    /// Read a bedtools genome file (`.genome`), with contig names and lengths in two
+    /// columns. Empty lines and comments starting with `#` are skipped.
+    pub fn read_genome<R: BufRead>(reader: R) -> Result<Self, GenomeDictError> {
+        Self::read_columns(reader)
+    }
+
+    /// Read a Picard sequence dictionary (`.dict`), using the `SN` and `LN` fields
+    /// of its `@SQ` lines.
+    pub fn read_dict<R: BufRead>(reader: R) -> Result<Self, GenomeDictError> {
+        let mut dict = GenomeDict::new();
+        for line in reader.lines() {
+            let line = line?;
+            if !line.starts_with("@SQ") {
+                continue;
+            }
+            let field = |tag: &str| line.split('\t').find_map(|field| field.strip_prefix(tag));
+            let (contig, len) = match (field("SN:"), field("LN:").map(str::parse)) {
+                (Some(contig), Some(Ok(len))) => (contig, len),
+                _ => return Err(GenomeDictError::InvalidLine(line.clone())),
+            };
+            dict.push(contig, len)?;
+        }
+        Ok(dict)
+    }
+
+    /// Read a genome dictionary from a file, with the format given by the extension:
+    /// `.fai`, `.dict` or `.genome`.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, GenomeDictError> {
+        let path = path.as_ref();
+        let read = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("fai") => Self::read_fai,
+            Some("dict") => Self::read_dict,
+            Some("genome") => Self::read_genome,
+            _ => return Err(GenomeDictError::UnknownFormat(path.to_owned())),
+        };
+        read(BufReader::new(File::open(path)?))
+    }
+
+    // Read contig names and lengths from the first two columns of a file.
+    fn read_columns<R: BufRead>(reader: R) -> Result<Self, GenomeDictError> {
+        let mut dict = GenomeDict::new();
+        for line in reader.lines() {
+            let line = line?;
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut fields = line.split_whitespace();
+            let (contig, len) = match (fields.next(), fields.next().map(str::parse)) {
+                (Some(contig), Some(Ok(len))) => (contig, len),
+                _ => return Err(GenomeDictError::InvalidLine(line.clone())),
+            };
+            dict.push(contig, len)?;
+        }
+        Ok(dict)
+    }
+
+    /// Number of contigs.
+    pub fn len(&self) -> usize {
+        self.contigs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.contigs.is_empty()
+    }
+
+    /// Iterate over the contigs and their lengths, in reference order.
+    pub fn contigs(&self) -> impl Iterator<Item = (&str, Length)> {
+        self.contigs
+            .iter()
+            .map(|(contig, len)| (contig.as_str(), *len))
+    }
+
+    /// Length of the given contig.
+    pub fn contig_len(&self, contig: &str) -> Option<Length> {
+        self.contig_index(contig).map(|i| self.contigs[i].1)
+    }
+
+    /// Index of the given contig in reference order.
+    pub fn contig_index(&self, contig: &str) -> Option<usize> {
+        self.ids.get(contig).copied()
+    }
+
+    /// Compare contigs by reference order. Unknown contigs come last, ordered by
+    /// name.
+    pub fn cmp_contigs(&self, a: &str, b: &str) -> Ordering {
+        let key = |contig| (self.contig_index(contig).unwrap_or(usize::MAX), contig);
+        key(a).cmp(&key(b))
+    }
+
+    /// Sort intervals by contig in reference order, then by start and end.
+    pub fn sort_intervals<I: AbstractInterval>(&self, intervals: &mut [I]) {
+        intervals.sort_by(|a, b| {
+            self.cmp_contigs(a.contig(), b.contig())
+                .then_with(|| a.range().start.cmp(&b.range().start))
+                .then_with(|| a.range().end.cmp(&b.range().end))
+        })
+    }
+
+    /// Sort loci by contig in reference order, then by position.
+    pub fn sort_loci<L: AbstractLocus>(&self, loci: &mut [L]) {
+        loci.sort_by(|a, b| {
+            self.cmp_contigs(a.contig(), b.contig())
+                .then_with(|| a.pos().cmp(&b.pos()))
+        })
+    }
+
+    /// Check that the interval lies on a known contig and within its bounds.
+    pub fn check_interval<I: AbstractInterval>(&self, interval: &I) -> Result<(), GenomeDictError> {
+        let len = self.known_len(interval.contig())?;
+        let range = interval.range();
+        if range.start > range.end || range.end > len {
+            return Err(GenomeDictError::OutOfBounds {
+                contig: interval.contig().to_owned(),
+                start: range.start,
+                end: range.end,
+                len,
+            });
+        }
+        Ok(())
+    }
+
+    /// Check that the locus lies on a known contig and within its bounds.
+    pub fn check_locus<L: AbstractLocus>(&self, locus: &L) -> Result<(), GenomeDictError> {
+        let len = self.known_len(locus.contig())?;
+        if locus.pos() >= len {
+            return Err(GenomeDictError::OutOfBounds {
+                contig: locus.contig().to_owned(),
+                start: locus.pos(),
+                end: locus.pos().saturating_add(1),
+                len,
+            });
+        }
+        Ok(())
+    }
+
+    /// Restrict the interval to the bounds of its contig. The result may be empty.
+    pub fn clamp_interval<I: AbstractInterval>(
+        &self,
+        interval: &I,
+    ) -> Result<Interval, GenomeDictError> {
+        let len = self.known_len(interval.contig())?;
+        let range = interval.range();
+        let end = range.end.min(len);
+        Ok(Interval::new(
+            interval.contig().to_owned(),
+            range.start.min(end)..end,
+        ))
+    }
+
+    /// Move the locus to the last position of its contig if it lies beyond. Fails
+    /// for empty contigs, which have no positions.
+    pub fn clamp_locus<L: AbstractLocus>(&self, locus: &L) -> Result<Locus, GenomeDictError> {
+        let len = self.known_len(locus.contig())?;
+        if len == 0 {
+            return Err(GenomeDictError::OutOfBounds {
+                contig: locus.contig().to_owned(),
+                start: locus.pos(),
+                end: locus.pos().saturating_add(1),
+                len,
+            });
+        }
+        let pos: Position = locus.pos().min(len - 1);
+        Ok(Locus::new(locus.contig().to_owned(), pos))
+    }
+
+    fn known_len(&self, contig: &str) -> Result<Length, GenomeDictError> {
+        self.contig_len(contig)
+            .ok_or_else(|| GenomeDictError::UnknownContig(contig.to_owned()))
+    }
+}
+
+/// Build a genome dictionary from contig names and lengths in reference order. This
+/// is also how genome dictionaries are deserialized, so that contig lookups always
+/// match the contigs.
+impl TryFrom<Vec<(String, Length)>> for GenomeDict {
+    type Error = GenomeDictError;
+
+    fn try_from(contigs: Vec<(String, Length)>) -> Result<Self, Self::Error> {
+        let mut dict = GenomeDict::new();
+        for (contig, len) in &contigs {
+            dict.push(contig, *len)?;
+        }
+        Ok(dict)
+    }
+}
+
+impl From<GenomeDict> for Vec<(String, Length)> {
+    fn from(dict: GenomeDict) -> Self {
+        dict.contigs
+    }
+}
+
+/// Errors that arise when reading or using genome dictionaries.
+#[derive(Error, Debug)]
+pub enum GenomeDictError {
+    #[error("invalid line in genome dictionary: {0:?}")]
+    InvalidLine(String),
+    #[error("contig {0:?} occurs more than once")]
+    DuplicateContig(String),
+    #[error("unknown contig {0:?}")]
+    UnknownContig(String),
+    #[error("range {start}..{end} exceeds length {len} of contig {contig:?}")]
+    OutOfBounds {
+        contig: String,
+        start: Position,
+        end: Position,
+        len: Length,
+    },
+    #[error("unknown genome dictionary format of file {0:?}")]
+    UnknownFormat(PathBuf),
+    #[error("error reading genome dictionary")]
+    Io(#[from] io::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read() {
+        let dict = "@HD\tVN:1.6\n\
+                    @SQ\tSN:chr1\tLN:1000\tM5:abc\n\
+                    @SQ\tSN:chr2\tLN:500\n";
+        let dict = GenomeDict::read_dict(dict.as_bytes()).unwrap();
+        let genome =
+            GenomeDict::read_genome("# comment\nchr1 1000\n\nchr2\t500\n".as_bytes()).unwrap();
+        assert_eq!(dict, genome);
+        assert_eq!(
+            dict.contigs().collect::<Vec<_>>(),
+            [("chr1", 1000), ("chr2", 500)]
+        );
+
+        assert!(matches!(
+            GenomeDict::read_fai("chr1\tabc\n".as_bytes()),
+            Err(GenomeDictError::InvalidLine(_))
+        ));
+        assert!(matches!(
+            GenomeDict::read_dict("@SQ\tSN:chr1\n".as_bytes()),
+            Err(GenomeDictError::InvalidLine(_))
+        ));
+        assert!(matches!(
+            GenomeDict::read_genome("chr1 10\nchr1 20\n".as_bytes()),
+            Err(GenomeDictError::DuplicateContig(_))
+        ));
+        assert_eq!(
+            GenomeDict::try_from(vec![("chr1".to_owned(), 1000), ("chr2".to_owned(), 500)])
+                .unwrap(),
+            dict
+        );
+        assert!(matches!(
+            GenomeDict::from_path("genome.txt"),
+            Err(GenomeDictError::UnknownFormat(_))
+        ));
+    }
+
+    #[test]
+    fn test_check_clamp_sort() {
+        let mut dict = GenomeDict::new();
+        dict.push("chr2", 100).unwrap();
+        dict.push("chr1", 50).unwrap();
+
+        let locus = Locus::new("chr1".to_owned(), 50);
+        assert!(matches!(
+            dict.check_locus(&locus),
+            Err(GenomeDictError::OutOfBounds { len: 50, .. })
+        ));
+        assert_eq!(dict.clamp_locus(&locus).unwrap().pos(), 49);
+        let mut with_empty = dict.clone();
+        with_empty.push("chrE", 0).unwrap();
+        let locus = Locus::new("chrE".to_owned(), Position::MAX);
+        assert!(matches!(
+            with_empty.clamp_locus(&locus),
+            Err(GenomeDictError::OutOfBounds { len: 0, .. })
+        ));
+        assert!(with_empty.check_locus(&locus).is_err());
+        let interval = Interval::new("chr1".to_owned(), 60..70);
+        assert_eq!(dict.clamp_interval(&interval).unwrap().range(), 50..50);
+        assert!(dict
+            .check_interval(&Interval::new("chr1".to_owned(), 0..50))
+            .is_ok());
+        assert!(matches!(
+            dict.check_interval(&Interval::new("chrM".to_owned(), 0..1)),
+            Err(GenomeDictError::UnknownContig(_))
+        ));
+
+        let mut intervals = vec![
+            Interval::new("chrM".to_owned(), 0..1),
+            Interval::new("chr1".to_owned(), 5..10),
+            Interval::new("chr2".to_owned(), 20..30),
+            Interval::new("chr1".to_owned(), 0..10),
+        ];
+        dict.sort_intervals(&mut intervals);
+        let order: Vec<_> = intervals
+            .iter()
+            .map(|i| (i.contig(), i.range().start))
+            .collect();
+        assert_eq!(order, [("chr2", 20), ("chr1", 0), ("chr1", 5), ("chrM", 0)]);
+    }
+}
@@ -0,0 +1,255 @@
+//! An immutable index over genomic intervals for fast overlap queries.
+//!
+//! For each contig, the intervals are sorted by start position and laid out as an
+//! implicit balanced binary search tree, where each node knows the maximum end
+//! position in its subtree. Queries visit only subtrees that can contain
+//! overlapping intervals, taking O(log n + k) time for k results.
+
+use std::collections::BTreeMap;
+use std::iter::FromIterator;
+use std::ops::Range;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::{AbstractInterval, AbstractLocus, Position};
+
+/// An immutable index of intervals, e.g. genomic features, keyed by contig. Intervals
+/// overlap if they share at least one position, as for
+/// [`AbstractInterval::overlaps`](trait.AbstractInterval.html#method.overlaps).
+///
+/// # Example
+///
+/// ```
+/// use bio_types::genome::{Interval, IntervalIndex, Locus};
+/// let index: IntervalIndex<Interval> = vec![
+///     Interval::new("chr1".to_owned(), 100..200),
+///     Interval::new("chr1".to_owned(), 150..300),
+///     Interval::new("chr2".to_owned(), 100..200),
+/// ]
+/// .into_iter()
+/// .collect();
+/// let query = Interval::new("chr1".to_owned(), 180..190);
+/// assert_eq!(index.overlapping(&query).count(), 2);
+/// let locus = Locus::new("chr1".to_owned(), 250);
+/// let hits: Vec<_> = index.containing(&locus).collect();
+/// assert_eq!(hits, [&Interval::new("chr1".to_owned(), 150..300)]);
+/// ```
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct IntervalIndex<T> {
+    contigs: BTreeMap<String, ContigIndex<T>>,
+}
+
+// The intervals of a contig, sorted by start, with the maximum end of the subtree
+// rooted at each position of the implicit tree.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, PartialEq, Eq, Clone)]
+struct ContigIndex<T> {
+    ranges: Vec<Range<Position>>,
+    max_ends: Vec<Position>,
+    items: Vec<T>,
+}
+
+impl<T: AbstractInterval> IntervalIndex<T> {
+    /// Build the index from the given intervals.
+    pub fn new<I: IntoIterator<Item = T>>(intervals: I) -> Self {
+        let mut contigs = BTreeMap::new();
+        for item in intervals {
+            contigs
+                .entry(item.contig().to_owned())
+                .or_insert_with(Vec::new)
+                .push(item);
+        }
+        IntervalIndex {
+            contigs: contigs
+                .into_iter()
+                .map(|(contig, items)| (contig, ContigIndex::new(items)))
+                .collect(),
+        }
+    }
+
+    /// Number of indexed intervals.
+    pub fn len(&self) -> usize {
+        self.contigs.values().map(|index| index.items.len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.contigs.is_empty()
+    }
+
+    /// Iterate over all intervals, ordered by contig and start position.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.contigs.values().flat_map(|index| index.items.iter())
+    }
+
+    /// Iterate over the intervals overlapping the query, ordered by start position.
+    pub fn overlapping<I: AbstractInterval>(&self, query: &I) -> Overlapping<'_, T> {
+        self.query(query.contig(), query.range())
+    }
+
+    /// Iterate over the intervals containing the locus, ordered by start position.
+    pub fn containing<L: AbstractLocus>(&self, locus: &L) -> Overlapping<'_, T> {
+        self.query(locus.contig(), locus.pos()..locus.pos().saturating_add(1))
+    }
+
+    fn query(&self, contig: &str, range: Range<Position>) -> Overlapping<'_, T> {
+        let index = self.contigs.get(contig);
+        let stack = match index {
+            Some(index) if range.start < range.end => vec![Node::Subtree(0, index.items.len())],
+            _ => Vec::new(),
+        };
+        Overlapping {
+            index,
+            range,
+            stack,
+        }
+    }
+}
+
+impl<T: AbstractInterval> FromIterator<T> for IntervalIndex<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(intervals: I) -> Self {
+        Self::new(intervals)
+    }
+}
+
+impl<T: AbstractInterval> ContigIndex<T> {
+    fn new(mut items: Vec<T>) -> Self {
+        items.sort_by_key(|item| {
+            let range = item.range();
+            (range.start, range.end)
+        });
+        let ranges: Vec<_> = items.iter().map(|item| item.range()).collect();
+        let mut max_ends = vec![0; ranges.len()];
+        Self::build(&ranges, &mut max_ends, 0, ranges.len());
+        ContigIndex {
+            ranges,
+            max_ends,
+            items,
+        }
+    }
+
+    // Fill in the maximum ends of the subtree of positions lo..hi, returning the
+    // maximum end.
+    fn build(
+        ranges: &[Range<Position>],
+        max_ends: &mut [Position],
+        lo: usize,
+        hi: usize,
+    ) -> Position {
+        if lo >= hi {
+            return 0;
+        }
+        let mid = lo + (hi - lo) / 2;
+        let max_end = ranges[mid]
+            .end
+            .max(Self::build(ranges, max_ends, lo, mid))
+            .max(Self::build(ranges, max_ends, mid + 1, hi));
+        max_ends[mid] = max_end;
+        max_end
+    }
+}
+
+// A pending step of the tree traversal.
+#[derive(Debug, Clone)]
+enum Node {
+    // The subtree of positions lo..hi.
+    Subtree(usize, usize),
+    // The interval at a position, to be checked for overlap.
+    Interval(usize),
+}
+
+/// Iterator over the intervals of an [`IntervalIndex`](struct.IntervalIndex.html)
+/// overlapping a query.
+#[derive(Debug, Clone)]
+pub struct Overlapping<'a, T> {
+    index: Option<&'a ContigIndex<T>>,
+    range: Range<Position>,
+    stack: Vec<Node>,
+}
+
+impl<'a, T> Iterator for Overlapping<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        let index = self.index?;
+        while let Some(node) = self.stack.pop() {
+            match node {
+                Node::Subtree(lo, hi) => {
+                    if lo >= hi {
+                        continue;
+                    }
+                    let mid = lo + (hi - lo) / 2;
+                    if index.max_ends[mid] <= self.range.start {
+                        continue;
+                    }
+                    // visit the left subtree first, to yield intervals in order
+                    if index.ranges[mid].start < self.range.end {
+                        self.stack.push(Node::Subtree(mid + 1, hi));
+                        self.stack.push(Node::Interval(mid));
+                    }
+                    self.stack.push(Node::Subtree(lo, mid));
+                }
+                Node::Interval(i) => {
+                    let range = &index.ranges[i];
+                    if range.start.max(self.range.start) < range.end.min(self.range.end) {
+                        return Some(&index.items[i]);
+                    }
+                }
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::genome::{Interval, Locus};
+
+    #[test]
+    fn test_interval_index() {
+        // pseudo-random intervals of various lengths
+        let mut seed = 42u64;
+        let mut random = |n: u64| {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (seed >> 33) % n
+        };
+        let intervals: Vec<_> = (0..500)
+            .map(|i| {
+                let start = random(10_000);
+                let len = if i % 50 == 0 {
+                    random(5_000)
+                } else {
+                    random(100)
+                };
+                let contig = if i % 3 == 0 { "chr1" } else { "chr2" };
+                Interval::new(contig.to_owned(), start..start + len)
+            })
+            .collect();
+        let index: IntervalIndex<_> = intervals.iter().cloned().collect();
+        assert_eq!(index.len(), 500);
+
+        for _ in 0..200 {
+            let start = random(11_000);
+            let query = Interval::new("chr2".to_owned(), start..start + random(300));
+            let mut expected: Vec<_> = intervals.iter().filter(|i| i.overlaps(&query)).collect();
+            expected.sort_by_key(|i| (i.range().start, i.range().end));
+            let found: Vec<_> = index.overlapping(&query).collect();
+            assert_eq!(found, expected);
+
+            let locus = Locus::new("chr1".to_owned(), start);
+            let expected = intervals
+                .iter()
+                .filter(|i| i.contains(locus.clone()))
+                .count();
+            assert_eq!(index.containing(&locus).count(), expected);
+        }
+        let query = Interval::new("chrX".to_owned(), 0..100_000);
+        assert_eq!(index.overlapping(&query).next(), None);
+        let locus = Locus::new("chr1".to_owned(), Position::MAX);
+        assert_eq!(index.containing(&locus).next(), None);
+    }
+}
@@ -0,0 +1,187 @@
+use std::cmp;
+use std::convert::TryFrom;
+use std::ops::Range;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+pub mod coord;
+pub mod dict;
+pub mod index;
+pub mod region;
+pub mod set;
+pub mod window;
+
+pub use self::coord::{CoordError, Pos0, Pos1};
+pub use self::dict::{GenomeDict, GenomeDictError};
+pub use self::index::IntervalIndex;
+pub use self::region::RegionError;
+pub use self::set::IntervalSet;
+pub use self::window::{WindowEdge, Windows};
+
+pub type Position = u64;
+pub type Length = u64;
+
+pub trait AbstractInterval {
+    /// Identifier for a genomic contig, e.g., a chromosome
+    fn contig(&self) -> &str;
+    /// Interval on the contig
+    fn range(&self) -> Range<Position>;
+    /// Return true if interval contains given locus.
+    fn contains<L>(&self, locus: L) -> bool
+    where
+        L: AbstractLocus,
+    {
+        self.contig() == locus.contig()
+            && locus.pos() >= self.range().start
+            && locus.pos() < self.range().end
+    }
+    /// Return true if the interval shares at least one position with the given
+    /// interval.
+    fn overlaps<I>(&self, other: &I) -> bool
+    where
+        I: AbstractInterval,
+    {
+        self.intersection(other).is_some()
+    }
+    /// Return the positions shared with the given interval, or `None` if there are
+    /// none.
+    ///
+    /// ```
+    /// use bio_types::genome::{AbstractInterval, Interval};
+    /// let a = Interval::new("chr1".to_owned(), 10..20);
+    /// let b = Interval::new("chr1".to_owned(), 15..30);
+    /// assert_eq!(a.intersection(&b), Some(Interval::new("chr1".to_owned(), 15..20)));
+    /// assert!(!a.overlaps(&Interval::new("chr1".to_owned(), 20..30)));
+    /// ```
+    fn intersection<I>(&self, other: &I) -> Option<Interval>
+    where
+        I: AbstractInterval,
+    {
+        let (a, b) = (self.range(), other.range());
+        let range = a.start.max(b.start)..a.end.min(b.end);
+        if self.contig() == other.contig() && range.start < range.end {
+            Some(Interval::new(self.contig().to_owned(), range))
+        } else {
+            None
+        }
+    }
+    /// Return the number of positions between the interval and the given interval,
+    /// which is zero if they overlap or are adjacent, or `None` if they lie on
+    /// different contigs.
+    fn distance<I>(&self, other: &I) -> Option<Length>
+    where
+        I: AbstractInterval,
+    {
+        if self.contig() != other.contig() {
+            return None;
+        }
+        let (a, b) = (self.range(), other.range());
+        Some(
+            a.start
+                .saturating_sub(b.end)
+                .max(b.start.saturating_sub(a.end)),
+        )
+    }
+}
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(new, Debug, PartialEq, Eq, Clone, Hash)]
+pub struct Interval {
+    contig: String,
+    range: Range<Position>,
+}
+
+impl PartialOrd for Interval {
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Interval {
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        self.contig.cmp(&other.contig).then_with(|| {
+            self.range
+                .start
+                .cmp(&other.range.start)
+                .then_with(|| self.range.end.cmp(&other.range.end))
+        })
+    }
+}
+
+impl Interval {
+    /// Construct an interval from 0-based, half-open coordinates, as in BED files.
+    pub fn from_half_open(contig: String, start: Pos0, end: Pos0) -> Self {
+        Interval::new(contig, start.get()..end.get())
+    }
+
+    /// Construct an interval from 1-based, closed coordinates, as in GFF files or
+    /// VCF records.
+    pub fn from_closed(contig: String, start: Pos1, end: Pos1) -> Self {
+        Interval::new(contig, Pos0::from(start).get()..end.get())
+    }
+
+    /// Mutable reference to interval on the contig
+    pub fn range_mut(&mut self) -> &mut Range<Position> {
+        &mut self.range
+    }
+}
+
+impl AbstractInterval for Interval {
+    fn contig(&self) -> &str {
+        &self.contig
+    }
+
+    fn range(&self) -> Range<Position> {
+        self.range.clone()
+    }
+}
+
+pub trait AbstractLocus {
+    /// Identifier for a genomic contig, e.g., a chromosome
+    fn contig(&self) -> &str;
+    /// Position on the contig
+    fn pos(&self) -> Position;
+    /// Position on the contig in 0-based coordinates
+    fn pos0(&self) -> Pos0 {
+        Pos0::new(self.pos())
+    }
+    /// Position on the contig in 1-based coordinates, or an error if the position
+    /// is `Position::MAX`
+    fn pos1(&self) -> Result<Pos1, CoordError> {
+        Pos1::try_from(self.pos0())
+    }
+}
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(new, Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Hash)]
+pub struct Locus {
+    contig: String,
+    pos: Position,
+}
+
+impl Locus {
+    /// Construct a locus from a 0-based position, as in BAM records.
+    pub fn from_pos0(contig: String, pos: Pos0) -> Self {
+        Locus::new(contig, pos.get())
+    }
+
+    /// Construct a locus from a 1-based position, as in SAM or VCF records.
+    pub fn from_pos1(contig: String, pos: Pos1) -> Self {
+        Locus::from_pos0(contig, pos.into())
+    }
+
+    /// Mutable reference to position.
+    pub fn pos_mut(&mut self) -> &mut Position {
+        &mut self.pos
+    }
+}
+
+impl AbstractLocus for Locus {
+    fn contig(&self) -> &str {
+        &self.contig
+    }
+
+    fn pos(&self) -> Position {
+        self.pos
+    }
+}
@@ -0,0 +1,258 @@
+//! Parsing and formatting of samtools-style region strings, e.g. `chr1`,
+//! `chr1:1,000`, `chr1:1000-2000` or `{HLA-A*01:01}:10-20`. Positions in region
+//! strings are 1-based and inclusive, and are converted to the 0-based, half-open
+//! coordinates of [`Interval`](../struct.Interval.html) and
+//! [`Locus`](../struct.Locus.html). Contig names containing a colon are enclosed
+//! in braces.
+
+use std::convert::TryFrom;
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+use thiserror::Error;
+
+use super::{AbstractInterval, AbstractLocus, Interval, Locus, Pos0, Pos1, Position};
+
+/// Parse a samtools region into an interval. A region without a range (`chr1`)
+/// covers the whole contig, and a region without an end (`chr1:1000` or
+/// `chr1:1000-`) extends to the end of the contig, which is represented by an end
+/// of `Position::MAX`.
+///
+/// # Example
+///
+/// ```
+/// use bio_types::genome::{AbstractInterval, Interval};
+/// let interval: Interval = "chr1:1,001-2,000".parse().unwrap();
+/// assert_eq!(interval.contig(), "chr1");
+/// assert_eq!(interval.range(), 1000..2000);
+/// assert_eq!(interval.to_string(), "chr1:1001-2000");
+///
+/// let interval: Interval = "{HLA-A*01:01}:10-20".parse().unwrap();
+/// assert_eq!(interval.contig(), "HLA-A*01:01");
+/// assert_eq!(interval.to_string(), "{HLA-A*01:01}:10-20");
+///
+/// let interval: Interval = "chrX".parse().unwrap();
+/// assert_eq!(interval.range(), 0..u64::MAX);
+/// ```
+impl FromStr for Interval {
+    type Err = RegionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (contig, range) = split_region(s)?;
+        let range = match range {
+            None => 0..Position::MAX,
+            Some(range) => {
+                let (begin, end) = match range.split_once('-') {
+                    Some((begin, end)) => (begin, Some(end)),
+                    None => (range, None),
+                };
+                let start = match (begin, end) {
+                    ("", Some(_)) => 0,
+                    _ => Pos0::from(parse_position(begin, s)?).get(),
+                };
+                // a closed 1-based end equals the half-open 0-based end
+                let end = match end {
+                    None | Some("") => Position::MAX,
+                    Some(end) => parse_position(end, s)?.get(),
+                };
+                if end < start {
+                    return Err(RegionError::InvalidRange(s.to_owned()));
+                }
+                start..end
+            }
+        };
+        Ok(Interval::new(contig.to_owned(), range))
+    }
+}
+
+impl Display for Interval {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write_contig(f, self.contig())?;
+        match (self.range.start, self.range.end) {
+            (0, Position::MAX) => Ok(()),
+            (start, Position::MAX) => {
+                write!(f, ":")?;
+                write_pos1(f, start)?;
+                write!(f, "-")
+            }
+            (start, end) => {
+                write!(f, ":")?;
+                write_pos1(f, start)?;
+                write!(f, "-{}", end)
+            }
+        }
+    }
+}
+
+/// Parse a samtools-style position (`chr1:1,000`) into a locus.
+///
+/// # Example
+///
+/// ```
+/// use bio_types::genome::{AbstractLocus, Locus};
+/// let locus: Locus = "chr1:1,000".parse().unwrap();
+/// assert_eq!(locus.pos(), 999);
+/// assert_eq!(locus.to_string(), "chr1:1000");
+/// ```
+impl FromStr for Locus {
+    type Err = RegionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match split_region(s)? {
+            (contig, Some(pos)) => Ok(Locus::from_pos1(contig.to_owned(), parse_position(pos, s)?)),
+            (_, None) => Err(RegionError::MissingPosition(s.to_owned())),
+        }
+    }
+}
+
+impl Display for Locus {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write_contig(f, self.contig())?;
+        write!(f, ":")?;
+        write_pos1(f, self.pos())
+    }
+}
+
+// Split a region into the contig and the part after the colon, if any.
+fn split_region(s: &str) -> Result<(&str, Option<&str>), RegionError> {
+    let (contig, rest) = match s.strip_prefix('{') {
+        Some(braced) => {
+            let end = braced
+                .find('}')
+                .ok_or_else(|| RegionError::UnmatchedBrace(s.to_owned()))?;
+            let rest = match &braced[end + 1..] {
+                "" => None,
+                rest => Some(
+                    rest.strip_prefix(':')
+                        .ok_or_else(|| RegionError::InvalidPosition(s.to_owned()))?,
+                ),
+            };
+            (&braced[..end], rest)
+        }
+        None => match s.rfind(':') {
+            Some(i) => (&s[..i], Some(&s[i + 1..])),
+            None => (s, None),
+        },
+    };
+    if contig.is_empty() {
+        return Err(RegionError::EmptyContig(s.to_owned()));
+    }
+    Ok((contig, rest))
+}
+
+// Parse a 1-based position, which may contain commas as thousands separators.
+fn parse_position(pos: &str, region: &str) -> Result<Pos1, RegionError> {
+    let digits: String = pos.chars().filter(|&c| c != ',').collect();
+    if digits.is_empty() || !digits.bytes().all(|c| c.is_ascii_digit()) {
+        return Err(RegionError::InvalidPosition(region.to_owned()));
+    }
+    digits
+        .parse()
+        .map_err(|_| RegionError::InvalidPosition(region.to_owned()))
+}
+
+// Write a 0-based position in 1-based coordinates, which exceed `Position` for
+// `Position::MAX`.
+fn write_pos1(f: &mut Formatter, pos: Position) -> fmt::Result {
+    match Pos1::try_from(Pos0::new(pos)) {
+        Ok(pos) => write!(f, "{}", pos),
+        Err(_) => write!(f, "{}", u128::from(pos) + 1),
+    }
+}
+
+fn write_contig(f: &mut Formatter, contig: &str) -> fmt::Result {
+    if contig.contains(':') {
+        write!(f, "{{{}}}", contig)
+    } else {
+        write!(f, "{}", contig)
+    }
+}
+
+/// Errors that arise when parsing region strings.
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum RegionError {
+    #[error("missing contig name in region {0:?}")]
+    EmptyContig(String),
+    #[error("unmatched brace in region {0:?}")]
+    UnmatchedBrace(String),
+    #[error("invalid position in region {0:?}")]
+    InvalidPosition(String),
+    #[error("region {0:?} ends before it starts")]
+    InvalidRange(String),
+    #[error("missing position in {0:?}")]
+    MissingPosition(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_interval() {
+        let parse = |s: &str| s.parse::<Interval>().map(|i| i.range());
+        assert_eq!(parse("chr1:1000"), Ok(999..Position::MAX));
+        assert_eq!(parse("chr1:1000-"), Ok(999..Position::MAX));
+        assert_eq!(parse("chr1:-2,000"), Ok(0..2000));
+        assert_eq!(parse("chr1:11-10"), Ok(10..10));
+        assert_eq!(parse("{chr1}"), Ok(0..Position::MAX));
+        let interval = Interval::new("chr1".to_owned(), Position::MAX..Position::MAX);
+        assert_eq!(interval.to_string(), "chr1:18446744073709551616-");
+        for region in [
+            "chr1",
+            "chr1:100-",
+            "chr1:1-1",
+            "chr1:11-10",
+            "{a:b}:5-6",
+            "{a:b}",
+        ] {
+            assert_eq!(region.parse::<Interval>().unwrap().to_string(), region);
+        }
+
+        assert_eq!(
+            parse("chr1:12-10"),
+            Err(RegionError::InvalidRange("chr1:12-10".to_owned()))
+        );
+        assert_eq!(
+            parse("chr1:0-10"),
+            Err(RegionError::InvalidPosition("chr1:0-10".to_owned()))
+        );
+        assert_eq!(
+            parse("chr1:1-+5"),
+            Err(RegionError::InvalidPosition("chr1:1-+5".to_owned()))
+        );
+        assert_eq!(
+            parse("chr1:"),
+            Err(RegionError::InvalidPosition("chr1:".to_owned()))
+        );
+        assert_eq!(
+            parse(":1-5"),
+            Err(RegionError::EmptyContig(":1-5".to_owned()))
+        );
+        assert_eq!(
+            parse("{a:b:1-5"),
+            Err(RegionError::UnmatchedBrace("{a:b:1-5".to_owned()))
+        );
+        assert_eq!(
+            parse("{a:b}1-5"),
+            Err(RegionError::InvalidPosition("{a:b}1-5".to_owned()))
+        );
+    }
+
+    #[test]
+    fn test_parse_locus() {
+        let locus: Locus = "{HLA-A*01:01}:1,234".parse().unwrap();
+        assert_eq!(locus, Locus::new("HLA-A*01:01".to_owned(), 1233));
+        assert_eq!(locus.pos1(), Ok(Pos1::new(1234).unwrap()));
+        assert_eq!(locus.to_string(), "{HLA-A*01:01}:1234");
+        let last = Locus::new("chr1".to_owned(), Position::MAX);
+        assert_eq!(last.to_string(), "chr1:18446744073709551616");
+        assert_eq!(
+            "chr1".parse::<Locus>(),
+            Err(RegionError::MissingPosition("chr1".to_owned()))
+        );
+        assert_eq!(
+            "chr1:1-5".parse::<Locus>(),
+            Err(RegionError::InvalidPosition("chr1:1-5".to_owned()))
+        );
+    }
+}
@@ -0,0 +1,392 @@
+//! Sets of genomic intervals, with operations in the style of bedtools: merging,
+//! union, intersection, subtraction, complement and closest-feature search.
+
+use std::collections::BTreeMap;
+use std::iter::FromIterator;
+use std::ops::Range;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::{AbstractInterval, AbstractLocus, Interval, Length, Position};
+
+/// A set of genomic positions, stored as sorted intervals per contig. Overlapping
+/// and adjacent intervals are merged and empty intervals are dropped, such that
+/// equal sets have equal representations.
+///
+/// # Example
+///
+/// ```
+/// use bio_types::genome::{Interval, IntervalSet};
+/// let set: IntervalSet = vec![
+///     Interval::new("chr1".to_owned(), 10..20),
+///     Interval::new("chr1".to_owned(), 15..30),
+///     Interval::new("chr1".to_owned(), 30..35),
+///     Interval::new("chr2".to_owned(), 0..5),
+/// ]
+/// .into_iter()
+/// .collect();
+/// assert_eq!(set.ranges("chr1"), [10..35]);
+/// assert_eq!(set.len(), 2);
+/// assert_eq!(set.covered_len(), 30);
+/// ```
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Default)]
+pub struct IntervalSet {
+    contigs: BTreeMap<String, Vec<Range<Position>>>,
+}
+
+impl IntervalSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the positions of an interval to the set.
+    pub fn insert<I: AbstractInterval>(&mut self, interval: &I) {
+        let range = interval.range();
+        if range.start >= range.end {
+            return;
+        }
+        if !self.contigs.contains_key(interval.contig()) {
+            self.contigs
+                .insert(interval.contig().to_owned(), Vec::new());
+        }
+        let ranges = self.contigs.get_mut(interval.contig()).unwrap();
+        // the intervals overlapping or adjacent to the new one
+        let first = ranges.partition_point(|r| r.end < range.start);
+        let last = ranges.partition_point(|r| r.start <= range.end);
+        let merged = if first < last {
+            ranges[first].start.min(range.start)..ranges[last - 1].end.max(range.end)
+        } else {
+            range
+        };
+        ranges.splice(first..last, std::iter::once(merged));
+    }
+
+    /// Number of intervals in the set, after merging.
+    pub fn len(&self) -> usize {
+        self.contigs.values().map(Vec::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.contigs.is_empty()
+    }
+
+    /// Number of positions in the set.
+    pub fn covered_len(&self) -> Length {
+        self.contigs
+            .values()
+            .flatten()
+            .map(|r| r.end - r.start)
+            .sum()
+    }
+
+    /// Contigs with positions in the set, in lexicographic order.
+    pub fn contigs(&self) -> impl Iterator<Item = &str> {
+        self.contigs.keys().map(String::as_str)
+    }
+
+    /// Sorted intervals of the set on the given contig.
+    pub fn ranges(&self, contig: &str) -> &[Range<Position>] {
+        self.contigs.get(contig).map_or(&[], Vec::as_slice)
+    }
+
+    /// Iterate over the intervals of the set, ordered by contig and position.
+    pub fn iter(&self) -> impl Iterator<Item = Interval> + '_ {
+        self.contigs.iter().flat_map(|(contig, ranges)| {
+            ranges
+                .iter()
+                .map(move |range| Interval::new(contig.clone(), range.clone()))
+        })
+    }
+
+    /// Return true if the set contains the given locus.
+    pub fn contains<L: AbstractLocus>(&self, locus: &L) -> bool {
+        let ranges = self.ranges(locus.contig());
+        let i = ranges.partition_point(|r| r.end <= locus.pos());
+        i < ranges.len() && ranges[i].start <= locus.pos()
+    }
+
+    /// Return true if the set shares at least one position with the given interval.
+    pub fn overlaps<I: AbstractInterval>(&self, interval: &I) -> bool {
+        let range = interval.range();
+        let ranges = self.ranges(interval.contig());
+        let i = ranges.partition_point(|r| r.end <= range.start);
+        range.start < range.end && i < ranges.len() && ranges[i].start < range.end
+    }
+
+    /// Merge intervals that are separated by at most `max_gap` positions, as
+    /// `bedtools merge -d`.
+    ///
+    /// ```
+    /// use bio_types::genome::{Interval, IntervalSet};
+    /// let set: IntervalSet = vec![
+    ///     Interval::new("chr1".to_owned(), 0..10),
+    ///     Interval::new("chr1".to_owned(), 15..20),
+    ///     Interval::new("chr1".to_owned(), 30..40),
+    /// ]
+    /// .into_iter()
+    /// .collect();
+    /// assert_eq!(set.merge(5).ranges("chr1"), [0..20, 30..40]);
+    /// ```
+    pub fn merge(&self, max_gap: Length) -> IntervalSet {
+        self.map_contigs(|ranges| {
+            let mut merged: Vec<Range<Position>> = Vec::with_capacity(ranges.len());
+            for range in ranges {
+                match merged.last_mut() {
+                    Some(last) if range.start - last.end <= max_gap => last.end = range.end,
+                    _ => merged.push(range.clone()),
+                }
+            }
+            merged
+        })
+    }
+
+    /// Positions contained in either set.
+    pub fn union(&self, other: &IntervalSet) -> IntervalSet {
+        let mut union = self.clone();
+        union.extend(other.iter());
+        union
+    }
+
+    /// Positions contained in both sets.
+    pub fn intersection(&self, other: &IntervalSet) -> IntervalSet {
+        self.map_contigs_with(other, |a, b| {
+            let mut intersection = Vec::new();
+            let (mut i, mut j) = (0, 0);
+            while i < a.len() && j < b.len() {
+                let start = a[i].start.max(b[j].start);
+                let end = a[i].end.min(b[j].end);
+                if start < end {
+                    intersection.push(start..end);
+                }
+                if a[i].end < b[j].end {
+                    i += 1;
+                } else {
+                    j += 1;
+                }
+            }
+            intersection
+        })
+    }
+
+    /// Positions contained in this set but not in the other one, as
+    /// `bedtools subtract`.
+    pub fn subtract(&self, other: &IntervalSet) -> IntervalSet {
+        self.map_contigs_with(other, |a, b| {
+            let mut difference = Vec::new();
+            let mut j = 0;
+            for range in a {
+                let mut start = range.start;
+                while j < b.len() && b[j].end <= start {
+                    j += 1;
+                }
+                for cut in b[j..].iter().take_while(|cut| cut.start < range.end) {
+                    if cut.start > start {
+                        difference.push(start..cut.start);
+                    }
+                    start = start.max(cut.end);
+                }
+                if start < range.end {
+                    difference.push(start..range.end);
+                }
+            }
+            difference
+        })
+    }
+
+    /// Positions of the given contigs that are not contained in the set, as
+    /// `bedtools complement`. Contigs are given with their lengths; positions on
+    /// other contigs are ignored.
+    ///
+    /// ```
+    /// use bio_types::genome::{Interval, IntervalSet};
+    /// let set: IntervalSet = vec![Interval::new("chr1".to_owned(), 10..20)]
+    ///     .into_iter()
+    ///     .collect();
+    /// let complement = set.complement(vec![("chr1", 100), ("chr2", 50)]);
+    /// assert_eq!(complement.ranges("chr1"), [0..10, 20..100]);
+    /// assert_eq!(complement.ranges("chr2"), [0..50]);
+    /// ```
+    pub fn complement<I, S>(&self, contig_lengths: I) -> IntervalSet
+    where
+        I: IntoIterator<Item = (S, Length)>,
+        S: AsRef<str>,
+    {
+        Self::from_contigs(contig_lengths.into_iter().map(|(contig, len)| {
+            let mut complement = Vec::new();
+            let mut pos = 0;
+            for range in self.ranges(contig.as_ref()) {
+                let end = range.start.min(len);
+                if pos < end {
+                    complement.push(pos..end);
+                }
+                pos = range.end;
+            }
+            if pos < len {
+                complement.push(pos..len);
+            }
+            (contig.as_ref().to_owned(), complement)
+        }))
+    }
+
+    /// The interval of the set closest to the given one, together with the number
+    /// of positions between them (zero if they overlap or are adjacent), as
+    /// `bedtools closest -d`. Ties are resolved in favour of the upstream
+    /// interval. Returns `None` if the set has no interval on the contig.
+    ///
+    /// ```
+    /// use bio_types::genome::{Interval, IntervalSet};
+    /// let set: IntervalSet = vec![
+    ///     Interval::new("chr1".to_owned(), 0..10),
+    ///     Interval::new("chr1".to_owned(), 30..40),
+    /// ]
+    /// .into_iter()
+    /// .collect();
+    /// let query = Interval::new("chr1".to_owned(), 22..25);
+    /// assert_eq!(set.closest(&query), Some((Interval::new("chr1".to_owned(), 30..40), 5)));
+    /// ```
+    pub fn closest<I: AbstractInterval>(&self, interval: &I) -> Option<(Interval, Length)> {
+        let query = interval.range();
+        let ranges = self.ranges(interval.contig());
+        // the first interval that ends after the start of the query
+        let i = ranges.partition_point(|r| r.end <= query.start);
+        let upstream = i
+            .checked_sub(1)
+            .map(|i| (&ranges[i], query.start - ranges[i].end));
+        let downstream = ranges
+            .get(i)
+            .map(|r| (r, r.start.saturating_sub(query.end)));
+        let (range, distance) = match (upstream, downstream) {
+            (Some(up), Some(down)) if down.1 < up.1 => down,
+            (Some(up), _) => up,
+            (None, down) => down?,
+        };
+        Some((
+            Interval::new(interval.contig().to_owned(), range.clone()),
+            distance,
+        ))
+    }
+
+    // Build a set from normalized intervals per contig, dropping empty contigs.
+    fn from_contigs<I>(contigs: I) -> Self
+    where
+        I: IntoIterator<Item = (String, Vec<Range<Position>>)>,
+    {
+        IntervalSet {
+            contigs: contigs
+                .into_iter()
+                .filter(|(_, ranges)| !ranges.is_empty())
+                .collect(),
+        }
+    }
+
+    // Apply a function to the intervals of each contig.
+    fn map_contigs<F>(&self, f: F) -> IntervalSet
+    where
+        F: Fn(&[Range<Position>]) -> Vec<Range<Position>>,
+    {
+        Self::from_contigs(
+            self.contigs
+                .iter()
+                .map(|(contig, ranges)| (contig.clone(), f(ranges))),
+        )
+    }
+
+    // Apply a function to the intervals of each contig and those of the other set on
+    // the same contig.
+    fn map_contigs_with<F>(&self, other: &IntervalSet, f: F) -> IntervalSet
+    where
+        F: Fn(&[Range<Position>], &[Range<Position>]) -> Vec<Range<Position>>,
+    {
+        Self::from_contigs(
+            self.contigs
+                .iter()
+                .map(|(contig, ranges)| (contig.clone(), f(ranges, other.ranges(contig)))),
+        )
+    }
+}
+
+impl<I: AbstractInterval> FromIterator<I> for IntervalSet {
+    fn from_iter<T: IntoIterator<Item = I>>(intervals: T) -> Self {
+        let mut set = IntervalSet::new();
+        set.extend(intervals);
+        set
+    }
+}
+
+impl<I: AbstractInterval> Extend<I> for IntervalSet {
+    fn extend<T: IntoIterator<Item = I>>(&mut self, intervals: T) {
+        for interval in intervals {
+            self.insert(&interval);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::genome::Locus;
+
+    fn set(intervals: &[(&str, Range<Position>)]) -> IntervalSet {
+        intervals
+            .iter()
+            .map(|(contig, range)| Interval::new(contig.to_string(), range.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn test_interval_set() {
+        let a = set(&[
+            ("chr1", 20..30),
+            ("chr1", 0..10),
+            ("chr1", 5..12),
+            ("chr1", 40..40),
+            ("chr2", 10..20),
+        ]);
+        let b = set(&[("chr1", 8..22), ("chr1", 25..26), ("chr3", 0..5)]);
+        assert_eq!(a.ranges("chr1"), [0..12, 20..30]);
+        assert!(a.contains(&Locus::new("chr1".to_owned(), 11)));
+        assert!(!a.contains(&Locus::new("chr1".to_owned(), 12)));
+        assert!(a.overlaps(&Interval::new("chr2".to_owned(), 19..25)));
+        assert!(!a.overlaps(&Interval::new("chr2".to_owned(), 20..25)));
+
+        assert_eq!(
+            a.union(&b),
+            set(&[("chr1", 0..30), ("chr2", 10..20), ("chr3", 0..5)])
+        );
+        assert_eq!(
+            a.intersection(&b),
+            set(&[("chr1", 8..12), ("chr1", 20..22), ("chr1", 25..26)])
+        );
+        assert_eq!(
+            a.subtract(&b),
+            set(&[
+                ("chr1", 0..8),
+                ("chr1", 22..25),
+                ("chr1", 26..30),
+                ("chr2", 10..20)
+            ])
+        );
+        assert_eq!(
+            a.complement(vec![("chr1", 25), ("chr3", 5)]),
+            set(&[("chr1", 12..20), ("chr3", 0..5)])
+        );
+        assert_eq!(a.merge(8), set(&[("chr1", 0..30), ("chr2", 10..20)]));
+        assert_eq!(a.merge(7).ranges("chr1"), [0..12, 20..30]);
+
+        let query = Interval::new("chr1".to_owned(), 14..16);
+        assert_eq!(
+            a.closest(&query),
+            Some((Interval::new("chr1".to_owned(), 0..12), 2))
+        );
+        let query = Interval::new("chr1".to_owned(), 35..36);
+        assert_eq!(
+            a.closest(&query),
+            Some((Interval::new("chr1".to_owned(), 20..30), 5))
+        );
+        let query = Interval::new("chr1".to_owned(), 25..50);
+        assert_eq!(a.closest(&query).unwrap().1, 0);
+        assert_eq!(a.closest(&Interval::new("chrX".to_owned(), 0..1)), None);
+    }
+}
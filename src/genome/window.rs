@@ -0,0 +1,349 @@
+//! Splitting contigs and intervals into windows, e.g. for binning coverage or GC
+//! content: fixed-size tiles, sliding windows with a step, or a number of parts of
+//! equal size. Windows can be generated for [`Interval`](../struct.Interval.html)s,
+//! for all contigs of a [`GenomeDict`](../struct.GenomeDict.html), and for stranded
+//! [`Contig`](../../annot/contig/struct.Contig.html) locations.
+
+use std::ops::Range;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::{AbstractInterval, GenomeDict, Interval, Length, Position};
+use crate::annot::contig::Contig;
+use crate::annot::loc::Loc;
+use crate::strand::ReqStrand;
+
+/// How to handle the last window if it would extend beyond the end of the split
+/// interval.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum WindowEdge {
+    /// Shorten the last window to end with the interval.
+    Truncate,
+    /// Leave out the last window.
+    Drop,
+    /// Keep the full size of the last window, extending beyond the interval.
+    Pad,
+}
+
+// The ranges of the windows of an interval.
+#[derive(Debug, Clone)]
+enum Ranges {
+    Sliding {
+        next: Position,
+        end: Position,
+        size: Length,
+        step: Length,
+        edge: WindowEdge,
+    },
+    Parts {
+        start: Position,
+        len: Length,
+        n: u64,
+        i: u64,
+    },
+}
+
+impl Ranges {
+    fn sliding(range: Range<Position>, size: Length, step: Length, edge: WindowEdge) -> Self {
+        assert!(size > 0, "window size must be positive");
+        assert!(step > 0, "window step must be positive");
+        Ranges::Sliding {
+            next: range.start,
+            end: range.end,
+            size,
+            step,
+            edge,
+        }
+    }
+
+    fn parts(range: Range<Position>, n: u64) -> Self {
+        assert!(n > 0, "number of parts must be positive");
+        Ranges::Parts {
+            start: range.start,
+            len: range.end.saturating_sub(range.start),
+            n,
+            i: 0,
+        }
+    }
+}
+
+impl Iterator for Ranges {
+    type Item = Range<Position>;
+
+    fn next(&mut self) -> Option<Range<Position>> {
+        match self {
+            Ranges::Sliding {
+                next,
+                end,
+                size,
+                step,
+                edge,
+            } => {
+                if *next >= *end {
+                    return None;
+                }
+                let start = *next;
+                let window_end = start.saturating_add(*size);
+                // the first window reaching the end of the interval is the last one
+                *next = if window_end >= *end {
+                    *end
+                } else {
+                    start.saturating_add(*step)
+                };
+                match edge {
+                    _ if window_end <= *end => Some(start..window_end),
+                    WindowEdge::Truncate => Some(start..*end),
+                    WindowEdge::Drop => None,
+                    WindowEdge::Pad => Some(start..window_end),
+                }
+            }
+            Ranges::Parts { start, len, n, i } => {
+                if *i >= *n {
+                    return None;
+                }
+                // the offset of part i, without overflow for long intervals
+                let offset = |i: u64| (i as u128 * *len as u128 / *n as u128) as Position;
+                let range = *start + offset(*i)..*start + offset(*i + 1);
+                *i += 1;
+                Some(range)
+            }
+        }
+    }
+}
+
+/// Iterator over the windows of an interval, see
+/// [`Interval::windows`](../struct.Interval.html#method.windows).
+#[derive(Debug, Clone)]
+pub struct Windows {
+    contig: String,
+    ranges: Ranges,
+}
+
+impl Iterator for Windows {
+    type Item = Interval;
+
+    fn next(&mut self) -> Option<Interval> {
+        let range = self.ranges.next()?;
+        Some(Interval::new(self.contig.clone(), range))
+    }
+}
+
+impl Interval {
+    /// Iterate over windows of the given size, starting every `step` positions. The
+    /// window that reaches the end of the interval is the last one, and is handled
+    /// according to `edge` if it extends beyond the end.
+    ///
+    /// Panics if `size` or `step` is zero.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bio_types::genome::{AbstractInterval, Interval, WindowEdge};
+    /// let interval = Interval::new("chr1".to_owned(), 100..125);
+    /// let windows: Vec<_> = interval
+    ///     .windows(10, 5, WindowEdge::Truncate)
+    ///     .map(|window| window.range())
+    ///     .collect();
+    /// assert_eq!(windows, [100..110, 105..115, 110..120, 115..125]);
+    /// let tiles: Vec<_> = interval
+    ///     .tiles(10, WindowEdge::Pad)
+    ///     .map(|tile| tile.range())
+    ///     .collect();
+    /// assert_eq!(tiles, [100..110, 110..120, 120..130]);
+    /// ```
+    pub fn windows(&self, size: Length, step: Length, edge: WindowEdge) -> Windows {
+        Windows {
+            contig: self.contig().to_owned(),
+            ranges: Ranges::sliding(self.range(), size, step, edge),
+        }
+    }
+
+    /// Iterate over adjacent, non-overlapping windows of the given size.
+    ///
+    /// Panics if `size` is zero.
+    pub fn tiles(&self, size: Length, edge: WindowEdge) -> Windows {
+        self.windows(size, size, edge)
+    }
+
+    /// Iterate over `n` adjacent parts of the interval, whose lengths differ by at
+    /// most one. Parts are empty if `n` exceeds the length of the interval.
+    ///
+    /// Panics if `n` is zero.
+    pub fn equal_parts(&self, n: u64) -> Windows {
+        Windows {
+            contig: self.contig().to_owned(),
+            ranges: Ranges::parts(self.range(), n),
+        }
+    }
+}
+
+impl GenomeDict {
+    /// Iterate over the whole contigs as intervals, in reference order.
+    pub fn intervals(&self) -> impl Iterator<Item = Interval> + '_ {
+        self.contigs()
+            .map(|(contig, len)| Interval::new(contig.to_owned(), 0..len))
+    }
+
+    /// Iterate over sliding windows of all contigs, in reference order, as for
+    /// [`Interval::windows`](struct.Interval.html#method.windows).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bio_types::genome::{GenomeDict, WindowEdge};
+    /// let dict = GenomeDict::read_genome("chr1\t2500\nchr2\t800\n".as_bytes()).unwrap();
+    /// let bins: Vec<_> = dict
+    ///     .tiles(1000, WindowEdge::Truncate)
+    ///     .map(|bin| bin.to_string())
+    ///     .collect();
+    /// assert_eq!(bins, ["chr1:1-1000", "chr1:1001-2000", "chr1:2001-2500", "chr2:1-800"]);
+    /// ```
+    pub fn windows(
+        &self,
+        size: Length,
+        step: Length,
+        edge: WindowEdge,
+    ) -> impl Iterator<Item = Interval> + '_ {
+        self.intervals()
+            .flat_map(move |contig| contig.windows(size, step, edge))
+    }
+
+    /// Iterate over tiles of all contigs, in reference order, as for
+    /// [`Interval::tiles`](struct.Interval.html#method.tiles).
+    pub fn tiles(&self, size: Length, edge: WindowEdge) -> impl Iterator<Item = Interval> + '_ {
+        self.windows(size, size, edge)
+    }
+
+    /// Iterate over `n` equal parts of each contig, in reference order, as for
+    /// [`Interval::equal_parts`](struct.Interval.html#method.equal_parts).
+    pub fn equal_parts(&self, n: u64) -> impl Iterator<Item = Interval> + '_ {
+        self.intervals()
+            .flat_map(move |contig| contig.equal_parts(n))
+    }
+}
+
+impl<R, S> Contig<R, S>
+where
+    R: Clone,
+    S: Into<Option<ReqStrand>> + Copy,
+{
+    /// Iterate over sliding windows of the contig location, as for
+    /// [`Interval::windows`](../../genome/struct.Interval.html#method.windows).
+    /// Windows on the reverse strand start at the high end of the location and
+    /// proceed towards lower coordinates, so that the last window lies at the 3'
+    /// end in either orientation.
+    ///
+    /// Panics if `size` or `step` is zero.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bio_types::annot::contig::Contig;
+    /// use bio_types::genome::WindowEdge;
+    /// use bio_types::strand::ReqStrand;
+    /// let gene = Contig::new("chrI".to_owned(), 1000, 25, ReqStrand::Reverse);
+    /// let windows: Vec<_> = gene
+    ///     .tiles(10, WindowEdge::Truncate)
+    ///     .map(|window| window.to_string())
+    ///     .collect();
+    /// assert_eq!(windows, ["chrI:1015-1025(-)", "chrI:1005-1015(-)", "chrI:1000-1005(-)"]);
+    /// ```
+    pub fn windows(
+        &self,
+        size: Length,
+        step: Length,
+        edge: WindowEdge,
+    ) -> impl Iterator<Item = Contig<R, S>> + '_ {
+        self.offset_windows(Ranges::sliding(
+            0..self.length() as Length,
+            size,
+            step,
+            edge,
+        ))
+    }
+
+    /// Iterate over adjacent, non-overlapping windows of the contig location, in
+    /// the direction of its strand.
+    ///
+    /// Panics if `size` is zero.
+    pub fn tiles(&self, size: Length, edge: WindowEdge) -> impl Iterator<Item = Contig<R, S>> + '_ {
+        self.windows(size, size, edge)
+    }
+
+    /// Iterate over `n` adjacent parts of the contig location, in the direction of
+    /// its strand, whose lengths differ by at most one.
+    ///
+    /// Panics if `n` is zero.
+    pub fn equal_parts(&self, n: u64) -> impl Iterator<Item = Contig<R, S>> + '_ {
+        self.offset_windows(Ranges::parts(0..self.length() as Length, n))
+    }
+
+    // Place windows given as offsets from the 5' end of the location.
+    fn offset_windows(&self, ranges: Ranges) -> impl Iterator<Item = Contig<R, S>> + '_ {
+        let reverse = self.strand().into() == Some(ReqStrand::Reverse);
+        ranges.map(move |range| {
+            let length = (range.end - range.start) as usize;
+            let start = if reverse {
+                self.start() + self.length() as isize - range.end as isize
+            } else {
+                self.start() + range.start as isize
+            };
+            Contig::new(self.refid().clone(), start, length, self.strand())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strand::NoStrand;
+
+    #[test]
+    fn test_windows() {
+        let interval = Interval::new("chr1".to_owned(), 0..22);
+        let ranges = |windows: Windows| windows.map(|w| w.range()).collect::<Vec<_>>();
+        assert_eq!(
+            ranges(interval.windows(10, 6, WindowEdge::Truncate)),
+            [0..10, 6..16, 12..22]
+        );
+        assert_eq!(
+            ranges(interval.tiles(10, WindowEdge::Drop)),
+            [0..10, 10..20]
+        );
+        assert_eq!(
+            ranges(interval.tiles(10, WindowEdge::Truncate)),
+            [0..10, 10..20, 20..22]
+        );
+        assert_eq!(
+            ranges(interval.windows(5, 10, WindowEdge::Truncate)),
+            [0..5, 10..15, 20..22]
+        );
+        assert_eq!(ranges(interval.tiles(30, WindowEdge::Drop)), []);
+        assert_eq!(
+            interval.tiles(30, WindowEdge::Pad).collect::<Vec<_>>(),
+            [Interval::new("chr1".to_owned(), 0..30)]
+        );
+        assert_eq!(
+            ranges(interval.equal_parts(4)),
+            [0..5, 5..11, 11..16, 16..22]
+        );
+        assert_eq!(
+            ranges(Interval::new("chr1".to_owned(), 5..7).equal_parts(3)),
+            [5..5, 5..6, 6..7]
+        );
+        let empty = Interval::new("chr1".to_owned(), 5..5);
+        assert_eq!(empty.tiles(10, WindowEdge::Pad).count(), 0);
+
+        let contig = Contig::new("chr1".to_owned(), 10, 22, NoStrand::Unknown);
+        let starts: Vec<_> = contig.equal_parts(2).map(|c| c.start()).collect();
+        assert_eq!(starts, [10, 21]);
+        let contig = Contig::new("chr1".to_owned(), 10, 22, ReqStrand::Reverse);
+        let windows: Vec<_> = contig
+            .tiles(10, WindowEdge::Pad)
+            .map(|c| (c.start(), c.length()))
+            .collect();
+        assert_eq!(windows, [(22, 10), (12, 10), (2, 10)]);
+    }
+}
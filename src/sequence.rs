@@ -6,7 +6,6 @@
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 use strum_macros::{AsRefStr, Display};
-use SequenceReadPairOrientation::None;
 
 /// A DNA base
 pub type Base = u8;
@@ -44,7 +43,7 @@ pub trait SequenceRead {
 /// // obtain string via `AsRef<&'static str>`
 /// assert_eq!(SequenceReadPairOrientation::R1F2.as_ref(), "R1F2");
 /// ```
-#[derive(Debug, Clone, Copy, PartialEq, Eq, AsRefStr, Display)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, AsRefStr, Display)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum SequenceReadPairOrientation {
     F1R2,
@@ -55,11 +54,6 @@ pub enum SequenceReadPairOrientation {
     R1R2,
     F2F1,
     R2R1,
+    #[default]
     None,
 }
-
-impl Default for SequenceReadPairOrientation {
-    fn default() -> Self {
-        None
-    }
-}
@@ -293,7 +293,7 @@ where
     fn same(&self, s1: &Self) -> bool {
         match (self, s1) {
             (&Option::None, &Option::None) => true,
-            (&Option::Some(ref x), &Option::Some(ref x1)) => x.same(x1),
+            (Option::Some(x), Option::Some(x1)) => x.same(x1),
             (_, _) => false,
         }
     }
@@ -1,4 +1,4 @@
-use crate::genome;
+use crate::genome::{self, AbstractLocus};
 use crate::sequence::{Base, Sequence};
 
 #[cfg(feature = "serde")]
@@ -40,3 +40,34 @@ impl Kind {
         self.len() == 0
     }
 }
+
+/// A variant at a genomic locus.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(new, Debug, PartialEq, Eq, Clone, Hash)]
+pub struct Variant {
+    locus: genome::Locus,
+    kind: Kind,
+}
+
+impl Variant {
+    /// Locus of the variant.
+    pub fn locus(&self) -> &genome::Locus {
+        &self.locus
+    }
+}
+
+impl AbstractLocus for Variant {
+    fn contig(&self) -> &str {
+        self.locus.contig()
+    }
+
+    fn pos(&self) -> genome::Position {
+        self.locus.pos()
+    }
+}
+
+impl AbstractVariant for Variant {
+    fn kind(&self) -> &Kind {
+        &self.kind
+    }
+}